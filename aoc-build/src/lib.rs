@@ -0,0 +1,158 @@
+//! Build script support for the year crates.
+//!
+//! Each year crate's `build.rs` calls [`generate_solutions`] and its `lib.rs` includes the
+//! result, so every binary in `src/bin` is also compiled as a module of the library and its
+//! `SOLUTION` (generated by `#[aoc::main]`) is listed in `SOLUTIONS` for the workspace runner.
+//! Binaries with `required-features` in the crate's manifest are only included when those
+//! features are enabled, as cargo only builds them then.
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Write `$OUT_DIR/solutions.rs` declaring a module per `src/bin/*.rs` and a `SOLUTIONS` slice
+pub fn generate_solutions() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    let bin_dir = Path::new(&manifest_dir).join("src").join("bin");
+    let manifest_path = Path::new(&manifest_dir).join("Cargo.toml");
+    println!("cargo:rerun-if-changed={}", bin_dir.display());
+    println!("cargo:rerun-if-changed={}", manifest_path.display());
+
+    let manifest = fs::read_to_string(&manifest_path).expect("Failed to read Cargo.toml");
+    let source = solutions_source(&solution_files(&bin_dir), &required_features(&manifest));
+    fs::write(Path::new(&out_dir).join("solutions.rs"), source)
+        .expect("Failed to write solutions.rs");
+}
+
+fn solution_files(bin_dir: &Path) -> Vec<PathBuf> {
+    let mut files = match fs::read_dir(bin_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+            .collect::<Vec<_>>(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    files
+}
+
+/// The `required-features` of each `[[bin]]` target that has any, by name
+fn required_features(manifest: &str) -> HashMap<String, Vec<String>> {
+    let manifest: toml::Table = manifest.parse().expect("Failed to parse Cargo.toml");
+    let Some(bins) = manifest.get("bin").and_then(|bins| bins.as_array()) else {
+        return HashMap::new();
+    };
+    bins.iter()
+        .filter_map(|bin| {
+            let name = bin.get("name")?.as_str()?;
+            let features = bin
+                .get("required-features")?
+                .as_array()?
+                .iter()
+                .filter_map(|feature| feature.as_str().map(String::from))
+                .collect();
+            Some((name.to_string(), features))
+        })
+        .collect()
+}
+
+fn solutions_source(files: &[PathBuf], required: &HashMap<String, Vec<String>>) -> String {
+    let mut source = String::new();
+    let mut modules = Vec::new();
+    for file in files {
+        let module = file.file_stem().unwrap().to_string_lossy().to_string();
+        let cfg = required.get(&module).map(|features| {
+            let features = features
+                .iter()
+                .map(|feature| format!("feature = {:?}", feature))
+                .collect::<Vec<_>>();
+            format!("#[cfg(all({}))]", features.join(", "))
+        });
+        // Binaries are written to be compiled on their own, so `main`, `tests` and the
+        // like are unused from the library's point of view, and the binary already reports
+        // anything else that is unused.  Clippy still lints them here.
+        if let Some(cfg) = &cfg {
+            writeln!(source, "{}", cfg).unwrap();
+        }
+        writeln!(source, "#[path = {:?}]", file.display().to_string()).unwrap();
+        writeln!(source, "#[allow(unused)]").unwrap();
+        writeln!(source, "mod {};", module).unwrap();
+        modules.push((module, cfg));
+    }
+
+    writeln!(source, "\npub const SOLUTIONS: &[aoc::Solution] = &[").unwrap();
+    for (module, cfg) in modules {
+        if let Some(cfg) = &cfg {
+            writeln!(source, "    {}", cfg).unwrap();
+        }
+        writeln!(source, "    {}::SOLUTION,", module).unwrap();
+    }
+    writeln!(source, "];").unwrap();
+    source
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn declares_modules_and_solutions() {
+        let source = solutions_source(
+            &[
+                PathBuf::from("/src/bin/day1.rs"),
+                PathBuf::from("/src/bin/day1_part2.rs"),
+            ],
+            &HashMap::new(),
+        );
+        assert_eq!(
+            source,
+            r#"#[path = "/src/bin/day1.rs"]
+#[allow(unused)]
+mod day1;
+#[path = "/src/bin/day1_part2.rs"]
+#[allow(unused)]
+mod day1_part2;
+
+pub const SOLUTIONS: &[aoc::Solution] = &[
+    day1::SOLUTION,
+    day1_part2::SOLUTION,
+];
+"#
+        );
+    }
+
+    #[test]
+    fn gates_binaries_with_required_features() {
+        let required = required_features(
+            r#"
+[package]
+name = "aoc2023"
+
+[[bin]]
+name = "day24_part2"
+required-features = ["z3"]
+
+[[bin]]
+name = "day25"
+"#,
+        );
+        assert_eq!(required.len(), 1);
+        let source = solutions_source(&[PathBuf::from("/src/bin/day24_part2.rs")], &required);
+        assert_eq!(
+            source,
+            r#"#[cfg(all(feature = "z3"))]
+#[path = "/src/bin/day24_part2.rs"]
+#[allow(unused)]
+mod day24_part2;
+
+pub const SOLUTIONS: &[aoc::Solution] = &[
+    #[cfg(all(feature = "z3"))]
+    day24_part2::SOLUTION,
+];
+"#
+        );
+    }
+}
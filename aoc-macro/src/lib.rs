@@ -1,48 +1,551 @@
 use proc_macro::TokenStream;
-use quote::quote;
-// use syn::{parse_macro_input, AttributeArgs, Ident, ItemFn, Lit, NestedMeta};
-use syn::{parse_macro_input, Ident, ItemFn};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, AttributeArgs, DeriveInput, Ident, ItemFn, Lit, Meta, NestedMeta};
+
+mod parse;
+
+/// The functions making up a solution, collected from the annotated function
+/// and the idents passed to the attribute, e.g. `#[aoc::main(part1, part2)]`.
+#[derive(Default)]
+struct Solution {
+    parse: Option<Ident>,
+    part1: Option<Ident>,
+    part2: Option<Ident>,
+    examples: Vec<Example>,
+    /// Explicit `year = ...`, `day = ...` and `part = ...` arguments
+    year: Option<syn::LitInt>,
+    day: Option<syn::LitInt>,
+    part: Option<syn::LitInt>,
+}
+
+/// An `example(...)` argument: an input and the expected answer for some parts
+struct Example {
+    input: TokenStream2,
+    /// Expected answers keyed by part, or `None` for a single-part solution
+    expected: Vec<(Option<u8>, String)>,
+}
+
+impl Solution {
+    fn set(&mut self, ident: &Ident) -> syn::Result<()> {
+        let slot = match ident.to_string().as_str() {
+            "parse" => &mut self.parse,
+            "part1" => &mut self.part1,
+            "part2" => &mut self.part2,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "expected one of `parse`, `part1` or `part2`",
+                ))
+            }
+        };
+        if slot.is_some() {
+            return Err(syn::Error::new_spanned(ident, "function listed twice"));
+        }
+        *slot = Some(ident.clone());
+        Ok(())
+    }
+
+    fn is_single_part(&self) -> bool {
+        self.parse.is_none() && self.part1.is_none() && self.part2.is_none()
+    }
+
+    fn set_id(&mut self, name_value: &syn::MetaNameValue) -> syn::Result<()> {
+        let slot = match name_value
+            .path
+            .get_ident()
+            .map(|i| i.to_string())
+            .as_deref()
+        {
+            Some("year") => &mut self.year,
+            Some("day") => &mut self.day,
+            Some("part") => &mut self.part,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &name_value.path,
+                    "expected one of `year`, `day` or `part`",
+                ))
+            }
+        };
+        let value = match &name_value.lit {
+            Lit::Int(value) => value,
+            lit => return Err(syn::Error::new_spanned(lit, "expected an integer")),
+        };
+        if slot.is_some() {
+            return Err(syn::Error::new_spanned(name_value, "given twice"));
+        }
+        *slot = Some(value.clone());
+        Ok(())
+    }
+
+    /// The puzzle id from the explicit arguments, falling back to what the path of the
+    /// annotated file tells
+    fn puzzle(&self, file: &str) -> syn::Result<TokenStream2> {
+        let inferred = puzzle_from_path(file);
+        let explicit = |lit: &Option<syn::LitInt>| -> syn::Result<Option<(usize, Span)>> {
+            lit.as_ref()
+                .map(|lit| Ok((lit.base10_parse::<usize>()?, lit.span())))
+                .transpose()
+        };
+        let pick = |lit: &Option<syn::LitInt>, inferred: Option<usize>, name: &str| match (
+            explicit(lit)?,
+            inferred,
+        ) {
+            (Some(explicit), _) => Ok(explicit),
+            (None, Some(inferred)) => Ok((inferred, Span::call_site())),
+            (None, None) => Err(syn::Error::new(
+                Span::call_site(),
+                format!(
+                    "can't tell the puzzle {} from the path {}; add `{} = ...` to #[aoc::main]",
+                    name, file, name
+                ),
+            )),
+        };
+
+        let (year, span) = pick(&self.year, inferred.0, "year")?;
+        if year < 2015 {
+            return Err(syn::Error::new(
+                span,
+                format!("{} has no Advent of Code", year),
+            ));
+        }
+        let (day, span) = pick(&self.day, inferred.1, "day")?;
+        if !(1..=25).contains(&day) {
+            return Err(syn::Error::new(span, "day must be between 1 and 25"));
+        }
+        if let (Some(part), false) = (&self.part, self.is_single_part()) {
+            return Err(syn::Error::new_spanned(
+                part,
+                "`part` only applies to single-part solutions; use `part1`/`part2`",
+            ));
+        }
+        let (part, span) = pick(&self.part, inferred.2.or(Some(1)), "part")?;
+        if !(1..=2).contains(&part) {
+            return Err(syn::Error::new(span, "part must be 1 or 2"));
+        }
+
+        let part = part as u8;
+        Ok(quote! { aoc::Puzzle { year: #year, day: #day, part: #part } })
+    }
+}
+
+/// Year, day and part as far as a path like `2023/src/bin/day7_part2.rs` gives them: the
+/// year from the last four-digit directory, the day and part from the file name
+fn puzzle_from_path(path: &str) -> (Option<usize>, Option<usize>, Option<usize>) {
+    let mut components = path.rsplit(['/', '\\']);
+    let stem = components.next().unwrap_or_default();
+    let stem = stem.strip_suffix(".rs").unwrap_or(stem);
+    let year = components
+        .find(|c| c.len() == 4 && c.chars().all(|c| c.is_ascii_digit()))
+        .and_then(|c| c.parse().ok());
+
+    let leading_number = |s: &str| {
+        let digits = s
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect::<String>();
+        digits.parse().ok()
+    };
+    let day = stem.strip_prefix("day").and_then(leading_number);
+    let part = stem
+        .find("_part")
+        .and_then(|i| leading_number(&stem[i + "_part".len()..]));
+    (year, day, part)
+}
+
+/// Parses `example(input = "...", part1 = 114, part2 = 2)`, where `input` may be replaced
+/// by `file = "path"` relative to the crate root and `answer` is used for single-part solutions
+fn parse_example(list: &syn::MetaList) -> syn::Result<Example> {
+    let mut input = None;
+    let mut expected = Vec::new();
+    for nested in &list.nested {
+        let name_value = match nested {
+            NestedMeta::Meta(Meta::NameValue(name_value)) => name_value,
+            _ => return Err(syn::Error::new_spanned(nested, "expected `key = value`")),
+        };
+        let key = name_value
+            .path
+            .get_ident()
+            .map(|ident| ident.to_string())
+            .unwrap_or_default();
+        let value = match &name_value.lit {
+            Lit::Str(s) => s.value(),
+            Lit::Int(i) => i.base10_digits().to_string(),
+            lit => return Err(syn::Error::new_spanned(lit, "expected a string or integer")),
+        };
+        match key.as_str() {
+            "input" => {
+                let lit = &name_value.lit;
+                input = Some(quote! { #lit });
+            }
+            "file" => {
+                input = Some(quote! {
+                    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", #value))
+                });
+            }
+            "answer" => expected.push((None, value)),
+            "part1" => expected.push((Some(1), value)),
+            "part2" => expected.push((Some(2), value)),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &name_value.path,
+                    "expected one of `input`, `file`, `answer`, `part1` or `part2`",
+                ))
+            }
+        }
+    }
+
+    match input {
+        Some(input) => Ok(Example { input, expected }),
+        None => Err(syn::Error::new_spanned(
+            list,
+            "example needs an `input` or a `file`",
+        )),
+    }
+}
 
 #[proc_macro_attribute]
-pub fn main(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn main(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
     let mut solve_function = parse_macro_input!(input as ItemFn);
-    solve_function.sig.ident = Ident::new("solve", solve_function.sig.ident.span());
 
-    let tokens = quote! {
+    let mut solution = Solution::default();
+    let mut errors = Vec::new();
+    for arg in &args {
+        let result = match arg {
+            NestedMeta::Meta(Meta::Path(path)) => match path.get_ident() {
+                Some(ident) => solution.set(ident),
+                None => Err(syn::Error::new_spanned(arg, "expected a function name")),
+            },
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("example") => {
+                parse_example(list).map(|example| solution.examples.push(example))
+            }
+            NestedMeta::Meta(Meta::NameValue(name_value)) => solution.set_id(name_value),
+            _ => Err(syn::Error::new_spanned(
+                arg,
+                "expected a function name, `year`/`day`/`part = ...` or `example(...)`",
+            )),
+        };
+        if let Err(e) = result {
+            errors.push(e);
+        }
+    }
+
+    // A function with any other name and no part arguments is the original one-part contract
+    let fn_name = solve_function.sig.ident.to_string();
+    let is_named_step = matches!(fn_name.as_str(), "parse" | "part1" | "part2");
+    if solution.is_single_part() && !is_named_step {
+        solve_function.sig.ident = Ident::new("solve", solve_function.sig.ident.span());
+    } else if let Err(e) = solution.set(&solve_function.sig.ident) {
+        errors.push(syn::Error::new_spanned(
+            &solve_function.sig.ident,
+            format!(
+                "#[aoc::main] with part arguments must be placed on `parse`, `part1` or `part2`: {}",
+                e
+            ),
+        ));
+    } else if solution.part1.is_none() && solution.part2.is_none() {
+        errors.push(syn::Error::new_spanned(
+            &solve_function.sig.ident,
+            "#[aoc::main] needs at least one of `part1` or `part2`",
+        ));
+    }
+    for (i, example) in solution.examples.iter().enumerate() {
+        for (part, _) in &example.expected {
+            let message = match part {
+                Some(_) if solution.is_single_part() => {
+                    "single-part solutions take `answer = ...` in examples".to_string()
+                }
+                None if !solution.is_single_part() => {
+                    "use `part1 = ...` or `part2 = ...` in examples".to_string()
+                }
+                Some(1) if solution.part1.is_none() => {
+                    format!("example {} checks part1, which is not defined", i + 1)
+                }
+                Some(2) if solution.part2.is_none() => {
+                    format!("example {} checks part2, which is not defined", i + 1)
+                }
+                _ => continue,
+            };
+            errors.push(syn::Error::new_spanned(&solve_function.sig.ident, message));
+        }
+    }
+    // `Span::file` needs Rust 1.88, hence the `rust-version` of the workspace crates
+    let puzzle = solution
+        .puzzle(&proc_macro::Span::call_site().file())
+        .unwrap_or_else(|e| {
+            errors.push(e);
+            TokenStream2::new()
+        });
+    if !errors.is_empty() {
+        let errors = errors.iter().map(syn::Error::to_compile_error);
+        return TokenStream::from(quote! { #solve_function #(#errors)* });
+    }
+
+    let main = if solution.is_single_part() {
+        single_part_main(&solve_function, &puzzle)
+    } else {
+        two_part_main(&solve_function, &solution, &puzzle)
+    };
+    let verify = example_tests(&solution);
+
+    TokenStream::from(quote! {
+        #main
+        #verify
+    })
+}
+
+/// Implements `FromStr` from an `#[aoc(fmt = "...")]` format, see `parse.rs`
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    parse::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates `verify_examples()`, called by `main` before the real input is run, and a
+/// `#[test]` per example so `cargo test` covers every day.  Without `example(...)`
+/// arguments a single-part solution's hand-written `tests()` function is used for both,
+/// while a solution with part arguments has nothing to verify.
+fn example_tests(solution: &Solution) -> TokenStream2 {
+    // The year crate's library includes every binary as a module too (see aoc-build), so
+    // only the binary gets the tests, or `cargo test` would run each of them twice
+    let compiled_as_binary = std::env::var_os("CARGO_BIN_NAME").is_some();
+
+    if solution.examples.is_empty() && !solution.is_single_part() {
+        return quote! {
+            fn verify_examples() -> anyhow::Result<()> {
+                println!("No examples to verify\n");
+                Ok(())
+            }
+        };
+    }
+    if solution.examples.is_empty() {
+        let test_module = compiled_as_binary.then(|| {
+            quote! {
+                #[cfg(test)]
+                mod aoc_examples {
+                    #[test]
+                    fn tests_pass() {
+                        super::tests().unwrap();
+                    }
+                }
+            }
+        });
+        return quote! {
+            fn verify_examples() -> anyhow::Result<()> {
+                println!("Verifying test");
+                tests()?;
+                println!("Tests pass.  Proceeding to run with personal input file\n");
+                Ok(())
+            }
+
+            #test_module
+        };
+    }
+
+    let mut examples = Vec::new();
+    let mut test_fns = Vec::new();
+    for (i, example) in solution.examples.iter().enumerate() {
+        let number = i + 1;
+        let input = &example.input;
+        for (part, expected) in &example.expected {
+            let run = match part {
+                None => quote! { solve(input) },
+                Some(part) => {
+                    let function = if *part == 1 {
+                        &solution.part1
+                    } else {
+                        &solution.part2
+                    };
+                    let parse = match &solution.parse {
+                        Some(parse) => quote! { let parsed = &#parse(input)?; },
+                        None => quote! { let parsed = input; },
+                    };
+                    let function = function.as_ref().expect("example parts are validated");
+                    quote! { { #parse #function(parsed) } }
+                }
+            };
+            let part_tokens = match part {
+                Some(part) => quote! { Some(#part) },
+                None => quote! { None },
+            };
+            let index = examples.len();
+            examples.push(quote! {
+                aoc::Example {
+                    number: #number,
+                    part: #part_tokens,
+                    expected: #expected,
+                    run: || {
+                        let input: &str = #input;
+                        Ok(#run?.to_string())
+                    },
+                }
+            });
+
+            let test_name = match part {
+                Some(part) => format_ident!("example_{}_part{}", number, part),
+                None => format_ident!("example_{}", number),
+            };
+            test_fns.push(quote! {
+                #[test]
+                fn #test_name() {
+                    if let Err(e) = aoc::run_examples(&super::examples()[#index..=#index]) {
+                        panic!("{}", e);
+                    }
+                }
+            });
+        }
+    }
+
+    let test_module = compiled_as_binary.then(|| {
+        quote! {
+            #[cfg(test)]
+            mod aoc_examples {
+                #(#test_fns)*
+            }
+        }
+    });
+    quote! {
+        fn examples() -> Vec<aoc::Example> {
+            vec![#(#examples),*]
+        }
+
+        fn verify_examples() -> anyhow::Result<()> {
+            println!("Verifying test");
+            aoc::run_examples(&examples())?;
+            println!("Tests pass.  Proceeding to run with personal input file\n");
+            Ok(())
+        }
+
+        #test_module
+    }
+}
+
+fn single_part_main(solve_function: &ItemFn, puzzle: &TokenStream2) -> TokenStream2 {
+    let main_fn = main_fn(puzzle, &quote! { &[#puzzle.part] });
+    quote! {
       // const INPUT: &str = include_str!(#input_path);
       #solve_function
-      fn main() -> anyhow::Result<()> {
 
-          // Make sure test case works before proceeding
-          println!("Verifying test");
-          tests()?;
-
-          println!("Tests pass.  Proceeding to run with personal input file\n");
-          let input = aoc::get_input_for_script(file!())?;
-
-          let now = ::std::time::Instant::now();
-          let elapsed = now.elapsed();
-          let answer = solve(&input)?;
-          println!("answer = {}", answer);
-        // println!("Part one: {}", p1);
-        // println!("Part two: {}", p2);
-          if elapsed.as_millis() > 0 {
-              println!("Time: {}ms", elapsed.as_millis());
-          } else {
-              println!("Time: {}μs", elapsed.as_micros());
+      fn run_solution(
+          input: &str,
+          part: Option<u8>,
+          repeat: usize,
+      ) -> anyhow::Result<aoc::SolutionRun> {
+          // The part comes from the attribute or the file name
+          let solved_part = SOLUTION.puzzle.part;
+          let mut parts = Vec::new();
+          if part.is_none_or(|p| p == solved_part) {
+              let (answer, time) = aoc::time(repeat, || solve(input))?;
+              parts.push(aoc::PartRun { part: solved_part, answer: answer.to_string(), time });
           }
+          Ok(aoc::SolutionRun { parse_time: None, parts })
+      }
+
+      #main_fn
+    }
+}
+
+fn two_part_main(annotated: &ItemFn, solution: &Solution, puzzle: &TokenStream2) -> TokenStream2 {
+    // Parts take a reference to the parsed input, or the raw input when there is no parse step
+    let parse = match &solution.parse {
+        Some(parse) => quote! {
+            let (parsed, parse_time) = aoc::time(repeat, || #parse(input))?;
+            let parsed = &parsed;
+            let parse_time = Some(parse_time);
+        },
+        None => quote! {
+            let parsed = input;
+            let parse_time = None;
+        },
+    };
+
+    let run_part = |part: u8, function: &Option<Ident>| match function {
+        Some(function) => quote! {
+            if part.is_none_or(|p| p == #part) {
+                let (answer, time) = aoc::time(repeat, || #function(parsed))?;
+                parts.push(aoc::PartRun { part: #part, answer: answer.to_string(), time });
+            }
+        },
+        None => quote! {},
+    };
+    let part1 = run_part(1, &solution.part1);
+    let part2 = run_part(2, &solution.part2);
+    let parts = [(1u8, &solution.part1), (2, &solution.part2)]
+        .into_iter()
+        .filter(|(_, function)| function.is_some())
+        .map(|(part, _)| part);
+    let main_fn = main_fn(puzzle, &quote! { &[#(#parts),*] });
 
+    quote! {
+      #annotated
+
+      fn run_solution(
+          input: &str,
+          part: Option<u8>,
+          repeat: usize,
+      ) -> anyhow::Result<aoc::SolutionRun> {
+          #parse
+          let mut parts = Vec::new();
+          #part1
+          #part2
+          Ok(aoc::SolutionRun { parse_time, parts })
+      }
+
+      #main_fn
+    }
+}
+
+/// The binary entry point, plus the `SOLUTION` entry that the year crate's library
+/// collects so the workspace runner can call `run_solution` in-process
+fn main_fn(puzzle: &TokenStream2, parts: &TokenStream2) -> TokenStream2 {
+    quote! {
+      pub const SOLUTION: aoc::Solution = aoc::Solution {
+          file: file!(),
+          puzzle: #puzzle,
+          parts: #parts,
+          run: run_solution,
+      };
+
+      fn main() -> anyhow::Result<()> {
           let args = aoc::get_cli_args();
-          if args.submit {
-              println!("Auto-submitting");
-              aoc::submit_script(file!(), &answer)?;
-          }
-          else {
-              println!("To submit, rerun with --submit-last");
+
+          // Make sure test case works before proceeding
+          verify_examples().inspect_err(aoc::parse::print_snippet)?;
+
+          if args.all_profiles {
+              return aoc::run_profiles(&SOLUTION, &args);
           }
+          let (input, load_time) = aoc::time(1, || aoc::load_input(&SOLUTION.puzzle, &args))?;
+          println!("Load time: {}", load_time);
 
-          Ok(())
+          let run = run_solution(&input, args.part, args.repeat)
+              .inspect_err(aoc::parse::print_snippet)?;
+          aoc::finish_script(&SOLUTION.puzzle, &args, &run)
       }
-    };
-    TokenStream::from(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infers_puzzle_from_path() {
+        assert_eq!(
+            puzzle_from_path("solutions/2023/src/bin/day9.rs"),
+            (Some(2023), Some(9), None)
+        );
+        assert_eq!(
+            puzzle_from_path("solutions/2023/src/bin/day24_part2_no_solver.rs"),
+            (Some(2023), Some(24), Some(2))
+        );
+        assert_eq!(
+            puzzle_from_path("solutions/2016/examples/day7_part2.rs"),
+            (Some(2016), Some(7), Some(2))
+        );
+        assert_eq!(puzzle_from_path("src/lib/grid.rs"), (None, None, None));
+    }
 }
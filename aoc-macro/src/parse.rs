@@ -0,0 +1,407 @@
+//! `#[derive(AocParse)]`: a `FromStr` implementation driven by a format string such as
+//! `#[aoc(fmt = "{x},{y},{z}~{x2},{y2},{z2}")]`.
+//!
+//! Literal text must match exactly and each `{field}` takes everything up to the next
+//! literal, or the rest of the line when it comes last, and parses it with the field's own
+//! `FromStr`, trying later occurrences of that literal when the rest of the format doesn't
+//! parse, so a value may contain it.  A `Vec` field is split on whitespace, or on the
+//! separator given as `{field:sep}`.  `{{` and `}}` stand for literal braces.  Enums put a
+//! format on every variant and parse as the first variant whose leading literal matches and
+//! whose format then parses, trying longer leading literals first.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{Attribute, Data, DeriveInput, Fields, Lit, LitStr, Meta, NestedMeta};
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field {
+        name: String,
+        separator: Option<String>,
+    },
+}
+
+/// Splits a format string into literals and `{field}` / `{field:sep}` placeholders
+fn parse_format(fmt: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = fmt.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err("unmatched `}`; write `}}` for a literal brace".to_string()),
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err("unclosed `{` in format".to_string()),
+                    }
+                }
+                let (name, separator) = match placeholder.split_once(':') {
+                    Some((name, separator)) => (name, Some(separator.to_string())),
+                    None => (placeholder.as_str(), None),
+                };
+                let name = name.trim().to_string();
+                if name.is_empty() {
+                    return Err("empty `{}` in format; name the field it fills".to_string());
+                }
+                if matches!(segments.last(), Some(Segment::Field { .. })) && literal.is_empty() {
+                    return Err(format!(
+                        "`{{{}}}` directly follows another field; put some literal text between them",
+                        name
+                    ));
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Field { name, separator });
+            }
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+/// The `fmt` of an `#[aoc(fmt = "...")]` attribute, if there is one
+fn format_attr(attrs: &[Attribute]) -> syn::Result<Option<LitStr>> {
+    let mut fmt = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("aoc")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "expected #[aoc(fmt = \"...\")]",
+                ))
+            }
+        };
+        for nested in &list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("fmt") => match &nv.lit {
+                    Lit::Str(lit) if fmt.is_none() => fmt = Some(lit.clone()),
+                    Lit::Str(_) => return Err(syn::Error::new_spanned(nv, "`fmt` given twice")),
+                    lit => return Err(syn::Error::new_spanned(lit, "expected a string")),
+                },
+                _ => return Err(syn::Error::new_spanned(nested, "expected `fmt = \"...\"`")),
+            }
+        }
+    }
+    Ok(fmt)
+}
+
+/// The element type of a `Vec<T>` field
+fn vec_element(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let last = path.path.segments.last()?;
+    if last.ident != "Vec" {
+        return None;
+    }
+    match &last.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Statements reading the fields in `fmt` off `rest`, followed by an expression building
+/// the value with `constructor`
+fn parse_fields(
+    fields: &Fields,
+    fmt: &LitStr,
+    constructor: TokenStream2,
+) -> syn::Result<TokenStream2> {
+    let segments = parse_format(&fmt.value()).map_err(|e| syn::Error::new_spanned(fmt, e))?;
+
+    let names = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => i.to_string(),
+        })
+        .collect::<Vec<_>>();
+    for segment in &segments {
+        if let Segment::Field { name, .. } = segment {
+            if !names.contains(name) {
+                return Err(syn::Error::new_spanned(fmt, format!("no field `{}`", name)));
+            }
+        }
+    }
+    for name in &names {
+        let uses = segments
+            .iter()
+            .filter(|s| matches!(s, Segment::Field { name: n, .. } if n == name))
+            .count();
+        if uses != 1 {
+            return Err(syn::Error::new_spanned(
+                fmt,
+                format!("field `{}` must appear exactly once in the format", name),
+            ));
+        }
+    }
+
+    let vars = names.iter().map(|name| format_ident!("__field_{}", name));
+    let value = match fields {
+        Fields::Named(named) => {
+            let idents = named.named.iter().map(|f| &f.ident);
+            quote! { #constructor { #(#idents: #vars),* } }
+        }
+        Fields::Unnamed(_) => quote! { #constructor(#(#vars),*) },
+        Fields::Unit => constructor,
+    };
+
+    // Built from the end, each step wrapping the ones after it, so that a field can try
+    // every occurrence of the literal after it until the rest of the format parses.  The
+    // first error is kept, which is the one the leftmost split points at.
+    let mut steps = quote! {
+        if rest.is_empty() {
+            return Ok(#value);
+        }
+        error.get_or_insert_with(|| anyhow::anyhow!("unexpected {:?} at the end", rest));
+    };
+    for (i, segment) in segments.iter().enumerate().rev() {
+        let after_field = i > 0 && matches!(segments[i - 1], Segment::Field { .. });
+        steps = match segment {
+            // Split off by the field before it
+            Segment::Literal(_) if after_field => steps,
+            Segment::Literal(literal) => quote! {
+                match rest.strip_prefix(#literal) {
+                    Some(rest) => { #steps }
+                    None => {
+                        error.get_or_insert_with(|| {
+                            anyhow::anyhow!("expected {:?} at {:?}", #literal, rest)
+                        });
+                    }
+                }
+            },
+            Segment::Field { name, separator } => {
+                let field = fields
+                    .iter()
+                    .zip(&names)
+                    .find_map(|(field, n)| (n == name).then_some(field))
+                    .unwrap();
+                let var = format_ident!("__field_{}", name);
+                let parse = field_parser(field, name, separator.as_deref(), fmt)?;
+                let parse_then_rest = quote! {
+                    match #parse {
+                        Ok(#var) => { #steps }
+                        Err(e) => {
+                            error.get_or_insert(e);
+                        }
+                    }
+                };
+                match segments.get(i + 1) {
+                    Some(Segment::Literal(next)) => quote! {
+                        if !rest.contains(#next) {
+                            error.get_or_insert_with(|| {
+                                anyhow::anyhow!("expected {:?} after {:?}", #next, rest)
+                            });
+                        }
+                        let splits = (0..rest.len())
+                            .filter(|&at| rest.is_char_boundary(at) && rest[at..].starts_with(#next));
+                        for at in splits {
+                            let (text, rest) = (&rest[..at], &rest[at + #next.len()..]);
+                            #parse_then_rest
+                        }
+                    },
+                    _ => quote! {
+                        let (text, rest) = (rest, "");
+                        #parse_then_rest
+                    },
+                }
+            }
+        };
+    }
+
+    Ok(quote! {
+        let rest: &str = s;
+        let mut error: Option<anyhow::Error> = None;
+        #steps
+        Err(error.unwrap_or_else(|| anyhow::anyhow!("no split of {:?} matches the format", s)))
+    })
+}
+
+/// An expression parsing `text` into `field`, as a `Result`
+fn field_parser(
+    field: &syn::Field,
+    name: &str,
+    separator: Option<&str>,
+    fmt: &LitStr,
+) -> syn::Result<TokenStream2> {
+    match (vec_element(&field.ty), separator) {
+        (Some(element), separator) => {
+            let items = match separator {
+                Some(separator) => quote! { text.split(#separator) },
+                None => quote! { text.split_whitespace() },
+            };
+            Ok(quote! {
+                if text.trim().is_empty() {
+                    Ok(Vec::new())
+                } else {
+                    #items
+                        .map(|item| {
+                            let item = item.trim();
+                            item.parse::<#element>().map_err(|e| {
+                                anyhow::anyhow!("`{}` item {:?}: {}", #name, item, e)
+                            })
+                        })
+                        .collect::<anyhow::Result<Vec<_>>>()
+                }
+            })
+        }
+        (None, Some(_)) => Err(syn::Error::new_spanned(
+            fmt,
+            format!("`{}` has a separator but is not a Vec", name),
+        )),
+        (None, None) => {
+            let ty = &field.ty;
+            Ok(quote! {
+                text.parse::<#ty>()
+                    .map_err(|e| anyhow::anyhow!("`{}` from {:?}: {}", #name, text, e))
+            })
+        }
+    }
+}
+
+/// The literal text a format starts with, "" if it starts with a field
+fn leading_literal(fmt: &LitStr) -> String {
+    match parse_format(&fmt.value()).ok().as_deref() {
+        Some([Segment::Literal(literal), ..]) => literal.clone(),
+        _ => String::new(),
+    }
+}
+
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let type_name = name.to_string();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fmt = format_attr(&input.attrs)?.ok_or_else(|| {
+                syn::Error::new_spanned(name, "#[derive(AocParse)] needs #[aoc(fmt = \"...\")]")
+            })?;
+            parse_fields(&data.fields, &fmt, quote! { Self })?
+        }
+        Data::Enum(data) => {
+            if let Some(attr) = input.attrs.iter().find(|attr| attr.path.is_ident("aoc")) {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "put the format on each variant of an enum",
+                ));
+            }
+            let mut variants = Vec::new();
+            for variant in &data.variants {
+                let fmt = format_attr(&variant.attrs)?.ok_or_else(|| {
+                    syn::Error::new_spanned(&variant.ident, "variant needs #[aoc(fmt = \"...\")]")
+                })?;
+                let ident = &variant.ident;
+                let parse = parse_fields(&variant.fields, &fmt, quote! { Self::#ident })?;
+                variants.push((leading_literal(&fmt), parse));
+            }
+            // Longest prefix first, so `%` is only tried after `%%` and "" comes last.  A
+            // variant that fails after its prefix matched falls through to the next one, so
+            // "turn {x}" still parses whatever "turn on {x}" doesn't.
+            variants.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+            let prefixes = variants
+                .iter()
+                .map(|(prefix, _)| prefix.clone())
+                .collect::<Vec<_>>();
+            let arms = variants.iter().map(|(prefix, parse)| {
+                quote! {
+                    if s.starts_with(#prefix) {
+                        match (|| -> anyhow::Result<Self> { #parse })() {
+                            Ok(value) => return Ok(value),
+                            Err(e) => {
+                                first_error.get_or_insert(e);
+                            }
+                        }
+                    }
+                }
+            });
+            quote! {
+                // The error of the most specific variant that matched, if any did
+                #[allow(unused_mut)]
+                let mut first_error: Option<anyhow::Error> = None;
+                #(#arms)*
+                if let Some(e) = first_error {
+                    return Err(e);
+                }
+                anyhow::bail!("expected it to start with one of {:?}", [#(#prefixes),*])
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "AocParse can't be derived for unions",
+            ))
+        }
+    };
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = anyhow::Error;
+
+            fn from_str(s: &str) -> anyhow::Result<Self> {
+                let s = s.trim_end_matches(['\r', '\n']);
+                let parse = || -> anyhow::Result<Self> { #body };
+                parse().map_err(|e| anyhow::anyhow!("Failed to parse {} from {:?}: {}", #type_name, s, e))
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, separator: Option<&str>) -> Segment {
+        Segment::Field {
+            name: name.to_string(),
+            separator: separator.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn splits_formats() {
+        assert_eq!(
+            parse_format("{x},{y}~{z}").unwrap(),
+            [
+                field("x", None),
+                Segment::Literal(",".to_string()),
+                field("y", None),
+                Segment::Literal("~".to_string()),
+                field("z", None),
+            ]
+        );
+        assert_eq!(
+            parse_format("{{x={x}}} -> {to:, }").unwrap(),
+            [
+                Segment::Literal("{x=".to_string()),
+                field("x", None),
+                Segment::Literal("} -> ".to_string()),
+                field("to", Some(", ")),
+            ]
+        );
+        assert!(parse_format("{a}{b}").is_err());
+        assert!(parse_format("{a").is_err());
+        assert!(parse_format("a}").is_err());
+        assert!(parse_format("{}").is_err());
+    }
+}
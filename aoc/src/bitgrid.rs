@@ -0,0 +1,326 @@
+//! A grid of booleans packed into `u64` words, for simulations that move every cell at
+//! once.  Each row starts on a new word, bit `x % 64` of word `x / 64` holding column `x`,
+//! so shifting by a row moves whole words and the padding bits always stay clear.
+
+use crate::DenseGrid2D;
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// All cells clear
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(64);
+        BitGrid {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    /// Set where `predicate` holds for the cell of `grid`
+    pub fn from_grid<T, F>(grid: &DenseGrid2D<T>, mut predicate: F) -> Self
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut bits = BitGrid::new(grid.width(), grid.height());
+        for (pos, cell) in grid.iter() {
+            if predicate(cell) {
+                bits.set(pos, true);
+            }
+        }
+        bits
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, (x, y): (usize, usize)) -> (usize, u64) {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is outside the {}x{} grid",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        (y * self.words_per_row + x / 64, 1 << (x % 64))
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> bool {
+        let (word, bit) = self.index(pos);
+        self.words[word] & bit != 0
+    }
+
+    pub fn set(&mut self, pos: (usize, usize), value: bool) {
+        let (word, bit) = self.index(pos);
+        if value {
+            self.words[word] |= bit;
+        } else {
+            self.words[word] &= !bit;
+        }
+    }
+
+    /// Number of set cells
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    /// Positions of the set cells in row-major order
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, word)| {
+            let y = i / self.words_per_row;
+            let base = (i % self.words_per_row) * 64;
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some((base + bit, y))
+            })
+        })
+    }
+
+    /// Every cell moved up one row; the top row falls off and the bottom one is clear
+    pub fn shift_north(&self) -> Self {
+        let mut shifted = BitGrid::new(self.width, self.height);
+        let row = self.words_per_row;
+        if self.height > 0 {
+            shifted.words[..row * (self.height - 1)].copy_from_slice(&self.words[row..]);
+        }
+        shifted
+    }
+
+    /// Every cell moved down one row
+    pub fn shift_south(&self) -> Self {
+        let mut shifted = BitGrid::new(self.width, self.height);
+        let row = self.words_per_row;
+        if self.height > 0 {
+            shifted.words[row..].copy_from_slice(&self.words[..row * (self.height - 1)]);
+        }
+        shifted
+    }
+
+    /// Every cell moved one column right; the last column falls off
+    pub fn shift_east(&self) -> Self {
+        let mut shifted = self.clone();
+        for row in shifted.rows_mut() {
+            let mut carry = 0;
+            for word in row.iter_mut() {
+                let next_carry = *word >> 63;
+                *word = (*word << 1) | carry;
+                carry = next_carry;
+            }
+        }
+        shifted.clear_padding();
+        shifted
+    }
+
+    /// Every cell moved one column left; the first column falls off
+    pub fn shift_west(&self) -> Self {
+        let mut shifted = self.clone();
+        for row in shifted.rows_mut() {
+            let mut carry = 0;
+            for word in row.iter_mut().rev() {
+                let next_carry = *word << 63;
+                *word = (*word >> 1) | carry;
+                carry = next_carry;
+            }
+        }
+        shifted
+    }
+
+    /// The words of each row.  A zero-width grid has no words at all, and so no rows.
+    fn rows_mut(&mut self) -> std::slice::ChunksMut<'_, u64> {
+        self.words.chunks_mut(self.words_per_row.max(1))
+    }
+
+    /// Clear the bits past the last column, which shifting or inverting may have set
+    fn clear_padding(&mut self) {
+        let used = self.width % 64;
+        if used == 0 {
+            return;
+        }
+        let mask = (1u64 << used) - 1;
+        for row in self.rows_mut() {
+            *row.last_mut().unwrap() &= mask;
+        }
+    }
+
+    fn assert_same_size(&self, other: &BitGrid) {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "Can't combine a {}x{} grid with a {}x{} one",
+            self.width,
+            self.height,
+            other.width,
+            other.height
+        );
+    }
+}
+
+macro_rules! bit_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl $assign_trait<&BitGrid> for BitGrid {
+            fn $assign_method(&mut self, other: &BitGrid) {
+                self.assert_same_size(other);
+                for (word, other) in self.words.iter_mut().zip(&other.words) {
+                    *word = *word $op *other;
+                }
+            }
+        }
+
+        impl $trait<&BitGrid> for &BitGrid {
+            type Output = BitGrid;
+            fn $method(self, other: &BitGrid) -> BitGrid {
+                let mut result = self.clone();
+                $assign_trait::$assign_method(&mut result, other);
+                result
+            }
+        }
+
+        impl $trait for BitGrid {
+            type Output = BitGrid;
+            fn $method(mut self, other: BitGrid) -> BitGrid {
+                $assign_trait::$assign_method(&mut self, &other);
+                self
+            }
+        }
+    };
+}
+
+bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+    fn not(self) -> BitGrid {
+        let mut result = self.clone();
+        for word in &mut result.words {
+            *word = !*word;
+        }
+        result.clear_padding();
+        result
+    }
+}
+
+impl Not for BitGrid {
+    type Output = BitGrid;
+    fn not(self) -> BitGrid {
+        !&self
+    }
+}
+
+/// `#` for set cells and `.` for clear ones
+impl fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                write!(f, "{}", if self.get((x, y)) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "BitGrid {}x{}", self.width, self.height)?;
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLATFORM: &str = "O....#....\nO.OO#....#\n.....##...\nOO.#O....O\n.O.....O#.\nO.#..O.#.#\n..O..#O..O\n.......O..\n#....###..\n#OO..#....\n";
+
+    #[test]
+    fn tilts_rocks_north() {
+        // 2023 day 14: roll every rock that has space above it until none can move
+        let grid: DenseGrid2D<char> = PLATFORM.parse().unwrap();
+        let walls = BitGrid::from_grid(&grid, |c| *c == '#');
+        let mut rocks = BitGrid::from_grid(&grid, |c| *c == 'O');
+        loop {
+            let free = !(&rocks | &walls);
+            let moving = &rocks & &free.shift_south();
+            if moving.is_empty() {
+                break;
+            }
+            rocks = (&rocks ^ &moving) | moving.shift_north();
+        }
+        let load = rocks
+            .positions()
+            .map(|(_, y)| grid.height() - y)
+            .sum::<usize>();
+        assert_eq!(load, 136);
+        assert_eq!(rocks.count_ones(), 18);
+    }
+
+    #[test]
+    fn shifts_across_words() {
+        let mut bits = BitGrid::new(70, 2);
+        bits.set((63, 0), true);
+        bits.set((69, 1), true);
+        let east = bits.shift_east();
+        assert_eq!(east.positions().collect::<Vec<_>>(), [(64, 0)]);
+        assert_eq!(east.shift_west().positions().collect::<Vec<_>>(), [(63, 0)]);
+        assert!(bits.shift_west().shift_west().get((67, 1)));
+        assert_eq!(
+            bits.shift_south().positions().collect::<Vec<_>>(),
+            [(63, 1)]
+        );
+        assert_eq!((!&bits).count_ones(), 138);
+    }
+
+    #[test]
+    fn hashes_by_contents() {
+        use std::collections::HashSet;
+        let grid: DenseGrid2D<char> = "#.\n.#\n".parse().unwrap();
+        let a = BitGrid::from_grid(&grid, |c| *c == '#');
+        let b = a.shift_east().shift_west() | a.shift_south().shift_north();
+        assert_eq!(a.to_string(), "#.\n.#\n");
+        assert_ne!(a, b);
+        let seen = [a.clone(), b, a].into_iter().collect::<HashSet<_>>();
+        assert_eq!(seen.len(), 2);
+    }
+
+    #[test]
+    fn handles_empty_grids() {
+        for (width, height) in [(0, 3), (3, 0), (0, 0)] {
+            let grid = BitGrid::new(width, height);
+            let shifted = [
+                grid.shift_north(),
+                grid.shift_south(),
+                grid.shift_east(),
+                grid.shift_west(),
+                !&grid,
+            ];
+            assert!(shifted.iter().all(|g| *g == grid));
+            assert_eq!(grid.positions().count(), 0);
+            assert_eq!(grid.to_string(), "\n".repeat(height));
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! Minimal adventofcode.com client for the requests where we need to see the response
+
+use crate::Config;
+use anyhow::{Context, Result};
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const BASE_URL: &str = "https://adventofcode.com";
+
+/// The server's verdict on a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Submitted before the cooldown from a previous answer ran out
+    TooSoon,
+    /// Anything else, e.g. the part already being solved
+    Other(String),
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::TooHigh => write!(f, "wrong (too high)"),
+            Verdict::TooLow => write!(f, "wrong (too low)"),
+            Verdict::TooSoon => write!(f, "answered too recently"),
+            Verdict::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmitResponse {
+    pub verdict: Verdict,
+    /// How long the server wants us to wait before the next submission
+    pub wait: Option<Duration>,
+}
+
+pub struct Client {
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(session: &str) -> Self {
+        Client::with_base_url(BASE_URL, session)
+    }
+
+    pub fn with_base_url(base_url: &str, session: &str) -> Self {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+        }
+    }
+
+    pub fn submit(
+        &self,
+        year: usize,
+        day: usize,
+        part: u8,
+        answer: &str,
+    ) -> Result<SubmitResponse> {
+        let url = format!("{}/{}/day/{}/answer", self.base_url, year, day);
+        let body = ureq::post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .with_context(|| format!("Failed to submit to {}", url))?
+            .into_string()?;
+        Ok(parse_submit_response(&body))
+    }
+
+    /// The puzzle input for this session's account
+    pub fn input(&self, year: usize, day: usize) -> Result<String> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let input = ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()
+            .with_context(|| format!("Failed to download {}", url))?
+            .into_string()?;
+        Ok(input)
+    }
+
+    /// The puzzle's page, including part two if this session has solved part one
+    pub fn puzzle_page(&self, year: usize, day: usize) -> Result<String> {
+        let url = format!("{}/{}/day/{}", self.base_url, year, day);
+        let mut request = ureq::get(&url);
+        if !self.session.is_empty() {
+            request = request.set("Cookie", &format!("session={}", self.session));
+        }
+        let page = request
+            .call()
+            .with_context(|| format!("Failed to fetch {}", url))?
+            .into_string()?;
+        Ok(page)
+    }
+}
+
+/// Session token of the selected profile, otherwise from `AOC_TOKEN`, then `session` in
+/// the cache directory and last the token elv was configured with, for checkouts that
+/// downloaded inputs through elv before.  Used for downloading inputs as well as submitting.
+pub fn session_token(config: &Config) -> Result<String> {
+    if let Some(token) = config.profile_token()? {
+        return Ok(token);
+    }
+    if let Ok(token) = std::env::var("AOC_TOKEN") {
+        return Ok(token.trim().to_string());
+    }
+    let path = config.cache_dir.join("session");
+    if let Ok(token) = std::fs::read_to_string(&path) {
+        return Ok(token.trim().to_string());
+    }
+    let elv = elv_config_path()
+        .and_then(|elv| std::fs::read_to_string(elv).ok())
+        .and_then(|contents| elv_token(&contents));
+    elv.with_context(|| {
+        format!(
+            "No session token: set AOC_TOKEN or write it to {}",
+            path.display()
+        )
+    })
+}
+
+/// Where elv keeps its settings, following the `directories` crate it uses
+fn elv_config_path() -> Option<PathBuf> {
+    let var = |name| std::env::var_os(name).map(PathBuf::from);
+    let dir = if cfg!(windows) {
+        var("APPDATA")?.join("elv").join("config")
+    } else if cfg!(target_os = "macos") {
+        var("HOME")?.join("Library/Application Support/elv")
+    } else {
+        var("XDG_CONFIG_HOME")
+            .or_else(|| var("HOME").map(|home| home.join(".config")))?
+            .join("elv")
+    };
+    Some(dir.join(".config"))
+}
+
+/// The `aoc.token` of an elv config file, which elv writes as an empty string when unset
+fn elv_token(contents: &str) -> Option<String> {
+    let config: toml::Table = contents.parse().ok()?;
+    let token = config.get("aoc")?.get("token")?.as_str()?.trim();
+    (!token.is_empty()).then(|| token.to_string())
+}
+
+/// Text of the page's `<article>` with the markup stripped
+pub fn article_text(html: &str) -> String {
+    let article = match (html.find("<article"), html.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &html[start..end],
+        _ => html,
+    };
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn parse_submit_response(html: &str) -> SubmitResponse {
+    let text = article_text(html);
+    let verdict = if text.contains("That's the right answer") {
+        Verdict::Correct
+    } else if text.contains("That's not the right answer") {
+        if text.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if text.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if text.contains("You gave an answer too recently") {
+        Verdict::TooSoon
+    } else {
+        Verdict::Other(text.clone())
+    };
+    SubmitResponse {
+        verdict,
+        wait: parse_wait(&text),
+    }
+}
+
+/// Cooldown announced in a response, e.g. "please wait 5 minutes" or "You have 1m 3s left to wait"
+fn parse_wait(text: &str) -> Option<Duration> {
+    if let Some((_, minutes, seconds)) =
+        lazy_regex::regex_captures!(r"You have (?:(\d+)m )?(\d+)s left to wait", text)
+    {
+        let minutes = minutes.parse::<u64>().unwrap_or(0);
+        let seconds = seconds.parse::<u64>().ok()?;
+        return Some(Duration::from_secs(minutes * 60 + seconds));
+    }
+    if text.contains("please wait one minute") {
+        return Some(Duration::from_secs(60));
+    }
+    lazy_regex::regex_captures!(r"please wait (\d+) minutes", text)
+        .and_then(|(_, minutes)| minutes.parse::<u64>().ok())
+        .map(|minutes| Duration::from_secs(minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_elv_tokens() {
+        let config = "[aoc]\ntoken = \"53616c7465645f5f\"\n\n[cli]\noutput_width = 120\n";
+        assert_eq!(elv_token(config), Some("53616c7465645f5f".to_string()));
+        assert_eq!(elv_token("[aoc]\ntoken = \"\"\n"), None);
+        assert_eq!(elv_token("[cli]\noutput_width = 120\n"), None);
+    }
+
+    #[test]
+    fn verdicts() {
+        let right = r#"<main><article><p>That's the right answer!  You are <span class="day-success">one gold star</span> closer.</p></article></main>"#;
+        let wrong = "<article><p>That's not the right answer.  If you're stuck, make sure you're using the full input data; please wait one minute before trying again.</p></article>";
+        let high = "<article><p>That's not the right answer; your answer is too high.  Because you have guessed incorrectly 4 times on this puzzle, please wait 5 minutes before trying again.</p></article>";
+        let soon = "<article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 3s left to wait. <a href=\"/2023/day/9\">[Return to Day 9]</a></p></article>";
+        let solved = "<article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article>";
+
+        let response = |verdict, wait: Option<u64>| SubmitResponse {
+            verdict,
+            wait: wait.map(Duration::from_secs),
+        };
+        assert_eq!(
+            parse_submit_response(right),
+            response(Verdict::Correct, None)
+        );
+        assert_eq!(
+            parse_submit_response(wrong),
+            response(Verdict::Wrong, Some(60))
+        );
+        assert_eq!(
+            parse_submit_response(high),
+            response(Verdict::TooHigh, Some(300))
+        );
+        assert_eq!(
+            parse_submit_response(soon),
+            response(Verdict::TooSoon, Some(63))
+        );
+        assert_eq!(
+            parse_submit_response(solved).verdict,
+            Verdict::Other(
+                "You don't seem to be solving the right level. Did you already complete it?"
+                    .to_string()
+            )
+        );
+    }
+}
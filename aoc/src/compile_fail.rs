@@ -0,0 +1,63 @@
+//! Attribute arguments that `#[aoc::main]` must reject at compile time.  Doctests are
+//! compiled outside any `YYYY/src/bin/dayN.rs`, so nothing can be inferred from the path.
+//!
+//! With everything given explicitly it compiles:
+//!
+//! ```no_run
+//! #[aoc::main(year = 2023, day = 1, example(input = "1\n2\n", answer = 3))]
+//! fn solve(input: &str) -> anyhow::Result<u32> {
+//!     Ok(aoc::parse_list::<u32>(input)?.iter().sum())
+//! }
+//! ```
+//!
+//! Without a year or day:
+//!
+//! ```compile_fail
+//! #[aoc::main(day = 1, example(input = "1\n2\n", answer = 3))]
+//! fn solve(input: &str) -> anyhow::Result<u32> {
+//!     Ok(aoc::parse_list::<u32>(input)?.iter().sum())
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[aoc::main(year = 2023, example(input = "1\n2\n", answer = 3))]
+//! fn solve(input: &str) -> anyhow::Result<u32> {
+//!     Ok(aoc::parse_list::<u32>(input)?.iter().sum())
+//! }
+//! ```
+//!
+//! Out of range:
+//!
+//! ```compile_fail
+//! #[aoc::main(year = 2014, day = 1, example(input = "1\n2\n", answer = 3))]
+//! fn solve(input: &str) -> anyhow::Result<u32> {
+//!     Ok(aoc::parse_list::<u32>(input)?.iter().sum())
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[aoc::main(year = 2023, day = 26, example(input = "1\n2\n", answer = 3))]
+//! fn solve(input: &str) -> anyhow::Result<u32> {
+//!     Ok(aoc::parse_list::<u32>(input)?.iter().sum())
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[aoc::main(year = 2023, day = 1, part = 3, example(input = "1\n2\n", answer = 3))]
+//! fn solve(input: &str) -> anyhow::Result<u32> {
+//!     Ok(aoc::parse_list::<u32>(input)?.iter().sum())
+//! }
+//! ```
+//!
+//! `part` on a solution that has both:
+//!
+//! ```compile_fail
+//! #[aoc::main(year = 2023, day = 1, part = 2, part1, example(input = "1\n2\n", part1 = 3))]
+//! fn parse(input: &str) -> anyhow::Result<Vec<u32>> {
+//!     aoc::parse_list(input)
+//! }
+//!
+//! fn part1(values: &[u32]) -> anyhow::Result<u32> {
+//!     Ok(values.iter().sum())
+//! }
+//! ```
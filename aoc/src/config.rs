@@ -0,0 +1,373 @@
+//! Where inputs and ledgers are cached and whether we may download.
+//!
+//! Settings come from, in increasing order of precedence: the defaults, the nearest
+//! `aoc.toml` in the current directory or one of its parents, the `AOC_CACHE_DIR` and
+//! `AOC_OFFLINE` environment variables, and finally the command line.
+//!
+//! Named profiles let one checkout work with several accounts.  Each has its own session
+//! token and keeps its inputs and ledgers under `<cache_dir>/profiles/<name>`:
+//!
+//! ```toml
+//! [profiles.alice]
+//! token_env = "AOC_TOKEN_ALICE"
+//!
+//! [profiles.bob]
+//! token_file = "~/.config/aoc/bob.session"
+//! ```
+
+use crate::Puzzle;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+/// Command line flags shared by the solution binaries and the `aoc` runner
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct ConfigArgs {
+    /// Directory holding cached inputs and answer ledgers [default: ~/.aoc]
+    #[arg(long)]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Fail instead of downloading inputs that aren't cached
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Download inputs that aren't cached, even if aoc.toml or AOC_OFFLINE says offline
+    #[arg(long, conflicts_with = "offline")]
+    pub online: bool,
+
+    /// Use the inputs, answers and session token of this profile from aoc.toml
+    #[arg(long)]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub cache_dir: PathBuf,
+    pub offline: bool,
+    /// Selected profile, `None` for the default account
+    pub profile: Option<String>,
+    pub profiles: BTreeMap<String, Profile>,
+    /// Template for the day files written by `aoc new`
+    pub template: Option<PathBuf>,
+}
+
+/// Where a named profile's session token comes from.  Without either setting it is read
+/// from `session` in the profile's cache directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Environment variable holding the session token
+    pub token_env: Option<String>,
+    /// File holding the session token
+    pub token_file: Option<PathBuf>,
+}
+
+/// The subset of settings that may appear in `aoc.toml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    cache_dir: Option<PathBuf>,
+    offline: Option<bool>,
+    profile: Option<String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+    template: Option<PathBuf>,
+}
+
+impl Config {
+    /// Settings from `aoc.toml` and the environment
+    pub fn load() -> Result<Config> {
+        Config::from_args(&ConfigArgs::default())
+    }
+
+    /// Settings from `aoc.toml` and the environment, overridden by command line flags
+    pub fn from_args(args: &ConfigArgs) -> Result<Config> {
+        // Only needed for the default cache directory and paths starting with `~`
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let home = home.as_deref();
+        let mut config = Config {
+            // Set from HOME at the end unless something else sets it
+            cache_dir: PathBuf::new(),
+            offline: false,
+            profile: None,
+            profiles: BTreeMap::new(),
+            template: None,
+        };
+
+        if let Some(path) = find_config_file(&std::env::current_dir()?) {
+            let contents = std::fs::read_to_string(&path)?;
+            let file: ConfigFile = toml::from_str(&contents)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            let base = path.parent().unwrap_or(Path::new("."));
+            config
+                .apply(file, base, home)
+                .with_context(|| format!("Failed to apply {}", path.display()))?;
+        }
+
+        if let Ok(dir) = std::env::var("AOC_CACHE_DIR") {
+            config.cache_dir = expand_home(Path::new(&dir), home)?;
+        }
+        if let Ok(offline) = std::env::var("AOC_OFFLINE") {
+            config.offline = !matches!(offline.as_str(), "" | "0" | "false");
+        }
+        if let Ok(profile) = std::env::var("AOC_PROFILE") {
+            config.profile = Some(profile);
+        }
+
+        if let Some(dir) = &args.cache_dir {
+            config.cache_dir = expand_home(dir, home)?;
+        }
+        if args.offline || args.online {
+            config.offline = args.offline;
+        }
+        if let Some(profile) = &args.profile {
+            config.profile = Some(profile.clone());
+        }
+        if config.cache_dir.as_os_str().is_empty() {
+            let home = home.context(
+                "HOME is not set, so there is no default cache directory; use --cache-dir or AOC_CACHE_DIR",
+            )?;
+            config.cache_dir = home.join(".aoc");
+        }
+
+        match config.profile.take() {
+            Some(profile) => config.with_profile(&profile),
+            None => Ok(config),
+        }
+    }
+
+    /// The same settings for another profile
+    pub fn with_profile(&self, name: &str) -> Result<Config> {
+        if !self.profiles.contains_key(name) {
+            bail!(
+                "Unknown profile {:?}, expected one of: {}",
+                name,
+                self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+            );
+        }
+        Ok(Config {
+            profile: Some(name.to_string()),
+            ..self.clone()
+        })
+    }
+
+    /// The default account followed by every named profile
+    pub fn all_profiles(&self) -> Vec<Config> {
+        let default = Config {
+            profile: None,
+            ..self.clone()
+        };
+        std::iter::once(default)
+            .chain(self.profiles.keys().map(|name| Config {
+                profile: Some(name.clone()),
+                ..self.clone()
+            }))
+            .collect()
+    }
+
+    pub fn profile_name(&self) -> &str {
+        self.profile.as_deref().unwrap_or("default")
+    }
+
+    /// Cache directory of the selected profile
+    pub fn namespace_dir(&self) -> PathBuf {
+        match &self.profile {
+            Some(profile) => self.cache_dir.join("profiles").join(profile),
+            None => self.cache_dir.clone(),
+        }
+    }
+
+    /// Session token of a named profile, or `None` to use the default account's
+    pub fn profile_token(&self) -> Result<Option<String>> {
+        let profile = match &self.profile {
+            Some(name) => &self.profiles[name],
+            None => return Ok(None),
+        };
+        if let Some(var) = &profile.token_env {
+            let token = std::env::var(var).with_context(|| {
+                format!(
+                    "Profile {} expects a token in ${}",
+                    self.profile_name(),
+                    var
+                )
+            })?;
+            return Ok(Some(token.trim().to_string()));
+        }
+        let path = profile
+            .token_file
+            .clone()
+            .unwrap_or_else(|| self.namespace_dir().join("session"));
+        let token = std::fs::read_to_string(&path).with_context(|| {
+            format!(
+                "Profile {} expects a token in {}",
+                self.profile_name(),
+                path.display()
+            )
+        })?;
+        Ok(Some(token.trim().to_string()))
+    }
+
+    /// Apply settings from a config file, resolving relative paths against its directory
+    fn apply(&mut self, file: ConfigFile, base: &Path, home: Option<&Path>) -> Result<()> {
+        if let Some(dir) = file.cache_dir {
+            self.cache_dir = base.join(expand_home(&dir, home)?);
+        }
+        if let Some(offline) = file.offline {
+            self.offline = offline;
+        }
+        if let Some(profile) = file.profile {
+            self.profile = Some(profile);
+        }
+        if let Some(template) = file.template {
+            self.template = Some(base.join(expand_home(&template, home)?));
+        }
+        for (name, mut profile) in file.profiles {
+            if let Some(path) = profile.token_file {
+                profile.token_file = Some(base.join(expand_home(&path, home)?));
+            }
+            self.profiles.insert(name, profile);
+        }
+        Ok(())
+    }
+
+    pub fn puzzle_dir(&self, puzzle: &Puzzle) -> PathBuf {
+        self.namespace_dir().join(puzzle.year.to_string())
+    }
+
+    pub fn input_path(&self, puzzle: &Puzzle) -> PathBuf {
+        self.puzzle_dir(puzzle).join(format!("{}.inp", puzzle.day))
+    }
+
+    pub fn description_path(&self, puzzle: &Puzzle) -> PathBuf {
+        self.puzzle_dir(puzzle).join(format!("{}.html", puzzle.day))
+    }
+
+    pub fn ledger_path(&self, puzzle: &Puzzle) -> PathBuf {
+        self.puzzle_dir(puzzle)
+            .join(format!("{}.answers", puzzle.day))
+    }
+}
+
+fn find_config_file(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+fn expand_home(path: &Path, home: Option<&Path>) -> Result<PathBuf> {
+    match path.strip_prefix("~") {
+        Ok(rest) => match home {
+            Some(home) => Ok(home.join(rest)),
+            None => bail!("HOME is not set, so {} can't be expanded", path.display()),
+        },
+        Err(_) => Ok(path.to_path_buf()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_file_paths() {
+        let home = Path::new("/home/me");
+        let mut config = Config {
+            cache_dir: home.join(".aoc"),
+            offline: false,
+            profile: None,
+            profiles: BTreeMap::new(),
+            template: None,
+        };
+        let file: ConfigFile =
+            toml::from_str("cache_dir = \"cache\"\noffline = true\ntemplate = \"day.rs\"").unwrap();
+        config
+            .apply(file, Path::new("/work/aoc"), Some(home))
+            .unwrap();
+        assert_eq!(config.cache_dir, PathBuf::from("/work/aoc/cache"));
+        assert!(config.offline);
+        assert_eq!(config.template, Some(PathBuf::from("/work/aoc/day.rs")));
+
+        let file: ConfigFile = toml::from_str("cache_dir = \"~/inputs\"").unwrap();
+        config
+            .apply(file, Path::new("/work/aoc"), Some(home))
+            .unwrap();
+        assert_eq!(config.cache_dir, PathBuf::from("/home/me/inputs"));
+
+        let puzzle = Puzzle {
+            year: 2023,
+            day: 9,
+            part: 1,
+        };
+        assert_eq!(
+            config.input_path(&puzzle),
+            PathBuf::from("/home/me/inputs/2023/9.inp")
+        );
+        assert!(toml::from_str::<ConfigFile>("cache = \"x\"").is_err());
+
+        // Without HOME only `~` paths are a problem
+        let file: ConfigFile = toml::from_str("cache_dir = \"/tmp/aoc\"").unwrap();
+        config.apply(file, Path::new("/work/aoc"), None).unwrap();
+        assert_eq!(config.cache_dir, PathBuf::from("/tmp/aoc"));
+        let file: ConfigFile = toml::from_str("cache_dir = \"~/inputs\"").unwrap();
+        assert!(config.apply(file, Path::new("/work/aoc"), None).is_err());
+    }
+
+    #[test]
+    fn profiles_have_their_own_namespace() {
+        let home = Path::new("/home/me");
+        let mut config = Config {
+            cache_dir: home.join(".aoc"),
+            offline: false,
+            profile: None,
+            profiles: BTreeMap::new(),
+            template: None,
+        };
+        let file: ConfigFile = toml::from_str(
+            "[profiles.alice]\ntoken_env = \"AOC_TOKEN_ALICE\"\n[profiles.bob]\ntoken_file = \"bob.session\"",
+        )
+        .unwrap();
+        config
+            .apply(file, Path::new("/work/aoc"), Some(home))
+            .unwrap();
+        assert_eq!(
+            config.profiles["bob"].token_file,
+            Some(PathBuf::from("/work/aoc/bob.session"))
+        );
+
+        let puzzle = Puzzle {
+            year: 2023,
+            day: 9,
+            part: 1,
+        };
+        let alice = config.with_profile("alice").unwrap();
+        assert_eq!(
+            alice.ledger_path(&puzzle),
+            PathBuf::from("/home/me/.aoc/profiles/alice/2023/9.answers")
+        );
+        assert!(config.with_profile("carol").is_err());
+        assert_eq!(config.profile_token().unwrap(), None);
+
+        let names = config
+            .all_profiles()
+            .iter()
+            .map(|c| c.profile_name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["default", "alice", "bob"]);
+    }
+
+    #[test]
+    fn finds_nearest_config_file() {
+        let root = std::env::temp_dir().join(format!("aoc-config-{}", std::process::id()));
+        let nested = root.join("solutions").join("2023");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.join(CONFIG_FILE), "offline = true").unwrap();
+
+        assert_eq!(find_config_file(&nested), Some(root.join(CONFIG_FILE)));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,147 @@
+//! Cycle detection for simulations that repeat: run a state forward until one repeats
+//! exactly, then skip whole periods to reach far-off iterations.  States are compared in
+//! full (by hash and equality), so two different states with the same score never match.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// States `start` onwards repeat every `period` steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The index of the state that iteration `n` repeats, `n` itself before the cycle
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// Runs `step` from `initial` until `limit` steps have been taken or a state repeats.
+/// Returns every state seen, the initial one first, and the cycle if one was found.
+fn run<S, F>(initial: S, mut step: F, limit: usize) -> (Vec<S>, Option<Cycle>)
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::from([(initial.clone(), 0)]);
+    let mut states = vec![initial];
+    while states.len() <= limit {
+        let next = step(states.last().unwrap());
+        if let Some(&start) = seen.get(&next) {
+            let period = states.len() - start;
+            return (states, Some(Cycle { start, period }));
+        }
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+    (states, None)
+}
+
+/// Where the states from `initial` start repeating.  Never returns if they don't.
+pub fn find<S, F>(initial: S, step: F) -> Cycle
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    run(initial, step, usize::MAX).1.unwrap()
+}
+
+/// The state after `n` steps from `initial`, skipping whole cycles once one is found
+pub fn nth_state<S, F>(initial: S, step: F, n: usize) -> S
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let (mut states, cycle) = run(initial, step, n);
+    match cycle {
+        Some(cycle) => states.swap_remove(cycle.reduce(n)),
+        None => states.swap_remove(n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BitGrid, DenseGrid2D};
+
+    #[test]
+    fn finds_prefix_and_period() {
+        let step = |x: &u32| (x * x + 1) % 255;
+        let cycle = find(3, step);
+
+        let mut states = vec![3];
+        for _ in 0..100 {
+            states.push(step(states.last().unwrap()));
+        }
+        assert_eq!(states[cycle.start], states[cycle.start + cycle.period]);
+        assert!((0..cycle.start).all(|i| !states[i + 1..].contains(&states[i])));
+        assert!((1..cycle.period).all(|p| states[cycle.start] != states[cycle.start + p]));
+        assert_eq!(nth_state(3, step, 77), states[77]);
+
+        assert_eq!(
+            find(0, |x: &u8| (x + 1) % 5),
+            Cycle {
+                start: 0,
+                period: 5
+            }
+        );
+        assert_eq!(nth_state(0u64, |x| x + 1, 10), 10);
+        assert_eq!(nth_state(7u64, |x| x + 1, 0), 7);
+    }
+
+    /// Rolls every rock as far as it goes; `ahead` moves a grid one step the way the rocks
+    /// roll, `behind` the opposite way
+    fn tilt(
+        rocks: &BitGrid,
+        walls: &BitGrid,
+        ahead: fn(&BitGrid) -> BitGrid,
+        behind: fn(&BitGrid) -> BitGrid,
+    ) -> BitGrid {
+        let mut rocks = rocks.clone();
+        loop {
+            let free = !(&rocks | walls);
+            let moving = &rocks & &behind(&free);
+            if moving.is_empty() {
+                return rocks;
+            }
+            rocks = (&rocks ^ &moving) | ahead(&moving);
+        }
+    }
+
+    #[test]
+    fn spin_cycles() {
+        // 2023 day 14 part 2
+        let platform: DenseGrid2D<char> = "O....#....\nO.OO#....#\n.....##...\nOO.#O....O\n.O.....O#.\nO.#..O.#.#\n..O..#O..O\n.......O..\n#....###..\n#OO..#....\n"
+            .parse()
+            .unwrap();
+        let walls = BitGrid::from_grid(&platform, |c| *c == '#');
+        let rocks = BitGrid::from_grid(&platform, |c| *c == 'O');
+        let spin = |rocks: &BitGrid| {
+            let rocks = tilt(rocks, &walls, BitGrid::shift_north, BitGrid::shift_south);
+            let rocks = tilt(&rocks, &walls, BitGrid::shift_west, BitGrid::shift_east);
+            let rocks = tilt(&rocks, &walls, BitGrid::shift_south, BitGrid::shift_north);
+            tilt(&rocks, &walls, BitGrid::shift_east, BitGrid::shift_west)
+        };
+
+        assert_eq!(
+            find(rocks.clone(), spin),
+            Cycle {
+                start: 3,
+                period: 7
+            }
+        );
+        let last = nth_state(rocks, spin, 1_000_000_000);
+        let load = last
+            .positions()
+            .map(|(_, y)| platform.height() - y)
+            .sum::<usize>();
+        assert_eq!(load, 64);
+    }
+}
@@ -0,0 +1,222 @@
+//! Puzzle descriptions: the page is downloaded once into the cache, rendered as Markdown
+//! for the terminal, and mined for `<pre><code>` blocks that are likely example inputs
+
+use crate::client::{session_token, Client};
+use crate::{Config, Puzzle};
+use anyhow::{bail, Context, Result};
+
+/// The puzzle page, from the cache or else downloaded and cached.  `refresh` downloads it
+/// again, e.g. to see part two after solving part one.
+pub fn puzzle_page(config: &Config, puzzle: &Puzzle, refresh: bool) -> Result<String> {
+    let path = config.description_path(puzzle);
+    if refresh || !path.exists() {
+        if config.offline {
+            bail!(
+                "Puzzle {} day {} is not cached at {} and offline mode is on",
+                puzzle.year,
+                puzzle.day,
+                path.display()
+            );
+        }
+        let client = Client::new(&session_token(config)?);
+        let html = client.puzzle_page(puzzle.year, puzzle.day)?;
+        println!("Downloaded puzzle to {}", path.display());
+        std::fs::create_dir_all(config.puzzle_dir(puzzle))?;
+        std::fs::write(&path, &html)?;
+        return Ok(html);
+    }
+    std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// The puzzle's `<article>`s as Markdown
+pub fn to_markdown(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_pre = false;
+    let mut link = None;
+    for token in articles(html) {
+        match token {
+            Token::Open("h2", _) => out.push_str("## "),
+            Token::Close("h2") | Token::Close("p") => out.push_str("\n\n"),
+            Token::Open("li", _) => out.push_str("- "),
+            Token::Close("li") | Token::Open("br", _) => out.push('\n'),
+            Token::Close("ul") => out.push('\n'),
+            Token::Open("pre", _) => {
+                in_pre = true;
+                out.push_str("```\n");
+            }
+            Token::Close("pre") => {
+                in_pre = false;
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                out.push_str("```\n\n");
+            }
+            // Emphasis inside code blocks would corrupt the example
+            Token::Open("em", _) | Token::Close("em") if !in_pre => out.push('*'),
+            Token::Open("code", _) | Token::Close("code") if !in_pre => out.push('`'),
+            Token::Open("a", attrs) => {
+                link = attribute(attrs, "href");
+                out.push('[');
+            }
+            Token::Close("a") => {
+                out.push(']');
+                if let Some(href) = link.take() {
+                    out.push_str(&format!("({})", href));
+                }
+            }
+            Token::Text(text) if in_pre => out.push_str(&unescape(text)),
+            Token::Text(text) => {
+                // Source line breaks inside a paragraph are just spaces
+                let text = unescape(text).replace('\n', " ");
+                if !(text.trim().is_empty() && (out.is_empty() || out.ends_with('\n'))) {
+                    out.push_str(&text);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut out = out.trim_end().to_string();
+    out.push('\n');
+    out
+}
+
+/// Text of every `<pre><code>` block, the usual place for example inputs
+pub fn examples(html: &str) -> Vec<String> {
+    let mut examples = Vec::new();
+    let mut current = None;
+    for token in articles(html) {
+        match token {
+            Token::Open("pre", _) => current = Some(String::new()),
+            Token::Close("pre") => examples.extend(current.take()),
+            Token::Text(text) => {
+                if let Some(example) = current.as_mut() {
+                    example.push_str(&unescape(text));
+                }
+            }
+            _ => {}
+        }
+    }
+    examples
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    /// Tag name and the raw attributes
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    Text(&'a str),
+}
+
+/// Tokens inside the page's `<article>` elements, or the whole page if it has none
+fn articles(html: &str) -> Vec<Token<'_>> {
+    let tokens = tokenize(html);
+    let mut inside = Vec::new();
+    let mut depth = 0;
+    for token in &tokens {
+        match token {
+            Token::Open("article", _) => depth += 1,
+            Token::Close("article") => depth -= 1,
+            _ if depth > 0 => inside.push(*token),
+            _ => {}
+        }
+    }
+    if inside.is_empty() {
+        tokens
+    } else {
+        inside
+    }
+}
+
+/// Just enough of an HTML tokenizer for Advent of Code pages: comments, doctypes, scripts
+/// and styles are dropped and tag names are taken as written
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim()));
+        } else if !tag.starts_with('!') {
+            let tag = tag.trim_end_matches('/');
+            let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            if name == "script" || name == "style" {
+                let close = format!("</{}>", name);
+                rest = rest
+                    .find(&close)
+                    .map_or("", |end| &rest[end + close.len()..]);
+            } else {
+                tokens.push(Token::Open(name, attrs));
+            }
+        }
+    }
+    tokens
+}
+
+fn attribute(attrs: &str, name: &str) -> Option<String> {
+    let start = attrs.find(&format!("{}=\"", name))? + name.len() + 2;
+    let len = attrs[start..].find('"')?;
+    Some(unescape(&attrs[start..start + len]))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY9: &str = include_str!("../fixtures/2023_day9.html");
+
+    #[test]
+    fn extracts_examples() {
+        let examples = examples(DAY9);
+        assert_eq!(examples.len(), 2);
+        assert_eq!(
+            examples[0],
+            "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45\n"
+        );
+        assert!(examples[1].starts_with("0   3   6   9  12  15   18\n"));
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let markdown = to_markdown(DAY9);
+        let lines = markdown.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "## --- Day 9: Mirage Maintenance ---");
+        assert!(lines[2].ends_with("standing at an *oasis*!"));
+        assert!(markdown.contains("```\n0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45\n```\n"));
+        assert!(markdown.contains("- The first history needs `x > 0` and `y < 3`.\n"));
+        assert!(markdown.contains("- Then add up `a & b`.\n"));
+        assert!(markdown.contains("you get `*114*`."));
+        assert!(markdown.contains("## --- Part Two ---"));
+        assert!(markdown.ends_with("gives `*2*`.\n"));
+        // Text outside the articles, like the previous answer, is left out
+        assert!(!markdown.contains("1938731307"));
+        assert!(!markdown.contains("Funny seeing you here"));
+    }
+}
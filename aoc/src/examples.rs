@@ -0,0 +1,99 @@
+use anyhow::{bail, Result};
+
+/// An example input with its expected answer, generated by `#[aoc::main(example(...))]`
+pub struct Example {
+    /// 1-based position of the example in the attribute
+    pub number: usize,
+    /// Part being checked, or `None` for a single-part solution
+    pub part: Option<u8>,
+    pub expected: &'static str,
+    pub run: fn() -> Result<String>,
+}
+
+impl Example {
+    fn label(&self) -> String {
+        match self.part {
+            Some(part) => format!("example {}, part {}", self.number, part),
+            None => format!("example {}", self.number),
+        }
+    }
+}
+
+/// Run each example, printing a report of expected vs actual for the ones that fail
+pub fn run_examples(examples: &[Example]) -> Result<()> {
+    let mut failures = 0;
+    for example in examples {
+        match (example.run)() {
+            Ok(actual) if actual == example.expected => {
+                println!("{} ... ok", example.label());
+            }
+            Ok(actual) => {
+                failures += 1;
+                println!("{} ... FAILED", example.label());
+                print!("{}", diff(example.expected, &actual));
+            }
+            Err(e) => {
+                failures += 1;
+                println!("{} ... FAILED", example.label());
+                println!("  error: {:#}", e);
+            }
+        }
+    }
+
+    if failures > 0 {
+        bail!("{} of {} examples failed", failures, examples.len());
+    }
+    Ok(())
+}
+
+/// Line-by-line comparison, marking expected lines with `-` and actual lines with `+`
+fn diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    let mut out = String::new();
+    for i in 0..std::cmp::max(expected.len(), actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => out.push_str(&format!("    {}\n", e)),
+            (e, a) => {
+                if let Some(e) = e {
+                    out.push_str(&format!("  - {}\n", e));
+                }
+                if let Some(a) = a {
+                    out.push_str(&format!("  + {}\n", a));
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_failures() {
+        let examples = [
+            Example {
+                number: 1,
+                part: Some(1),
+                expected: "114",
+                run: || Ok("114".to_string()),
+            },
+            Example {
+                number: 1,
+                part: Some(2),
+                expected: "2",
+                run: || Ok("3".to_string()),
+            },
+        ];
+        assert!(run_examples(&examples[..1]).is_ok());
+        let err = run_examples(&examples).unwrap_err();
+        assert_eq!(err.to_string(), "1 of 2 examples failed");
+    }
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        assert_eq!(diff("a\nb", "a\nc\nd"), "    a\n  - b\n  + c\n  + d\n");
+    }
+}
@@ -0,0 +1,318 @@
+//! Flood fills over a `DenseGrid2D`: BFS distances, connected regions and their shape, and
+//! the cells enclosed by a loop
+
+use crate::DenseGrid2D;
+use std::collections::{HashSet, VecDeque};
+
+/// Steps from the nearest of `starts` to every cell, `None` where unreachable.
+/// `can_move(from, to)` decides whether a step between two neighbouring cells is allowed.
+pub fn bfs_distances<T, I, F>(
+    grid: &DenseGrid2D<T>,
+    starts: I,
+    mut can_move: F,
+) -> DenseGrid2D<Option<usize>>
+where
+    I: IntoIterator<Item = (usize, usize)>,
+    F: FnMut((usize, usize), (usize, usize)) -> bool,
+{
+    let mut distances = DenseGrid2D::new(grid.width(), grid.height(), None);
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if distances[start].is_none() {
+            distances[start] = Some(0);
+            queue.push_back(start);
+        }
+    }
+    while let Some(pos) = queue.pop_front() {
+        let distance = distances[pos].unwrap();
+        for next in grid.neighbors4(pos) {
+            if distances[next].is_none() && can_move(pos, next) {
+                distances[next] = Some(distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// Labels every cell with its region, numbered from 0 in row-major order of first cell.
+/// Orthogonal neighbours are in the same region when `same(a, b)`.  Returns the labels
+/// and the number of regions.
+pub fn components<T, F>(grid: &DenseGrid2D<T>, mut same: F) -> (DenseGrid2D<usize>, usize)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let mut labels = DenseGrid2D::new(grid.width(), grid.height(), usize::MAX);
+    let mut count = 0;
+    for start in grid.positions() {
+        if labels[start] != usize::MAX {
+            continue;
+        }
+        labels[start] = count;
+        let mut stack = vec![start];
+        while let Some(pos) = stack.pop() {
+            for next in grid.neighbors4(pos) {
+                if labels[next] == usize::MAX && same(&grid[pos], &grid[next]) {
+                    labels[next] = count;
+                    stack.push(next);
+                }
+            }
+        }
+        count += 1;
+    }
+    (labels, count)
+}
+
+/// The connected regions of `grid`, in the order `components` numbers them
+pub fn regions<T, F>(grid: &DenseGrid2D<T>, same: F) -> Vec<Region>
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let (labels, count) = components(grid, same);
+    let mut regions = vec![Region::default(); count];
+    for (pos, label) in labels.iter() {
+        regions[*label].cells.insert(pos);
+    }
+    regions
+}
+
+/// A set of cells, usually one connected region
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Region {
+    pub cells: HashSet<(usize, usize)>,
+}
+
+impl Region {
+    pub fn new<I: IntoIterator<Item = (usize, usize)>>(cells: I) -> Self {
+        Region {
+            cells: cells.into_iter().collect(),
+        }
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn contains(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> bool {
+        match (x.checked_add_signed(dx), y.checked_add_signed(dy)) {
+            (Some(x), Some(y)) => self.cells.contains(&(x, y)),
+            _ => false,
+        }
+    }
+
+    /// Number of cell edges between the region and anything else
+    pub fn perimeter(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|pos| {
+                [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                    .iter()
+                    .filter(|delta| !self.contains(*pos, **delta))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Number of straight fence sections around the region, holes included.  A polygon
+    /// has as many sides as corners, so this counts corners.
+    pub fn sides(&self) -> usize {
+        let mut corners = 0;
+        for pos in &self.cells {
+            for (dx, dy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                let horizontal = self.contains(*pos, (dx, 0));
+                let vertical = self.contains(*pos, (0, dy));
+                let diagonal = self.contains(*pos, (dx, dy));
+                let outer = !horizontal && !vertical;
+                let inner = horizontal && vertical && !diagonal;
+                if outer || inner {
+                    corners += 1;
+                }
+            }
+        }
+        corners
+    }
+}
+
+/// Cells of a `width` x `height` grid inside the closed loop `path`, whose consecutive
+/// cells (and last and first) are orthogonal neighbours.
+///
+/// Scans each row keeping track of whether it is inside the loop, which flips on every
+/// loop cell connected to the cell above it.  A horizontal run of the loop then flips it
+/// once if it crosses the row (`L--7`) and twice or not at all if it turns back (`L--J`,
+/// `F--7`), so runs need no special casing.
+pub fn enclosed_by_loop(
+    width: usize,
+    height: usize,
+    path: &[(usize, usize)],
+) -> Vec<(usize, usize)> {
+    let mut on_loop = DenseGrid2D::new(width, height, false);
+    let mut goes_north = DenseGrid2D::new(width, height, false);
+    for (i, pos) in path.iter().enumerate() {
+        on_loop[*pos] = true;
+        let prev = path[(i + path.len() - 1) % path.len()];
+        let next = path[(i + 1) % path.len()];
+        let above = pos.1.checked_sub(1).map(|y| (pos.0, y));
+        if above.is_some_and(|above| above == prev || above == next) {
+            goes_north[*pos] = true;
+        }
+    }
+
+    let mut enclosed = Vec::new();
+    for y in 0..height {
+        let mut inside = false;
+        for x in 0..width {
+            if on_loop[(x, y)] {
+                inside ^= goes_north[(x, y)];
+            } else if inside {
+                enclosed.push((x, y));
+            }
+        }
+    }
+    enclosed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::Dir4;
+
+    /// Directions a 2023 day 10 pipe connects to
+    fn connections(pipe: char) -> &'static [Dir4] {
+        use Dir4::*;
+        match pipe {
+            '|' => &[North, South],
+            '-' => &[East, West],
+            'L' => &[North, East],
+            'J' => &[North, West],
+            '7' => &[South, West],
+            'F' => &[South, East],
+            'S' => &[North, East, South, West],
+            _ => &[],
+        }
+    }
+
+    fn connected(grid: &DenseGrid2D<char>, from: (usize, usize), to: (usize, usize)) -> bool {
+        Dir4::ALL.iter().any(|dir| {
+            grid.offset(from, dir.delta()) == Some(to)
+                && connections(grid[from]).contains(dir)
+                && connections(grid[to]).contains(&dir.reverse())
+        })
+    }
+
+    fn pipe_loop(grid: &DenseGrid2D<char>) -> Vec<(usize, usize)> {
+        let start = grid.find(&'S').unwrap();
+        let mut path = vec![start];
+        let mut prev = start;
+        let mut pos = grid
+            .neighbors4(start)
+            .find(|next| connected(grid, start, *next))
+            .unwrap();
+        while pos != start {
+            path.push(pos);
+            let next = grid
+                .neighbors4(pos)
+                .find(|next| *next != prev && connected(grid, pos, *next))
+                .unwrap();
+            (prev, pos) = (pos, next);
+        }
+        path
+    }
+
+    const FARTHEST: [(&str, usize); 2] = [
+        ("-L|F7\n7S-7|\nL|7||\n-L-J|\nL|-JF\n", 4),
+        ("7-F7-\n.FJ|7\nSJLL7\n|F--J\nLJ.LJ\n", 8),
+    ];
+
+    const ENCLOSED: [(&str, usize); 3] = [
+        (
+            "...........\n.S-------7.\n.|F-----7|.\n.||.....||.\n.||.....||.\n.|L-7.F-J|.\n.|..|.|..|.\n.L--J.L--J.\n...........\n",
+            4,
+        ),
+        (
+            ".F----7F7F7F7F-7....\n.|F--7||||||||FJ....\n.||.FJ||||||||L7....\nFJL7L7LJLJ||LJ.L-7..\nL--J.L7...LJS7F-7L7.\n....F-J..F7FJ|L7L7L7\n....L7.F7||L7|.L7L7|\n.....|FJLJ|FJ|F7|.LJ\n....FJL-7.||.||||...\n....L---J.LJ.LJLJ...\n",
+            8,
+        ),
+        (
+            "FF7FSF7F7F7F7F7F---7\nL|LJ||||||||||||F--J\nFL-7LJLJ||||||LJL-77\nF--JF--7||LJLJ7F7FJ-\nL---JF-JLJ.||-FJLJJ7\n|F|F-JF---7F7-L7L|7|\n|FFJF7L7F-JF7|JL---7\n7-L-JL7||F7|L7F-7F7|\nL.L7LFJ|||||FJL7||LJ\nL7JLJL-JLJLJL--JLJ.L\n",
+            10,
+        ),
+    ];
+
+    #[test]
+    fn bfs_finds_farthest_pipe() {
+        for (map, farthest) in FARTHEST {
+            let grid: DenseGrid2D<char> = map.parse().unwrap();
+            let start = grid.find(&'S').unwrap();
+            let distances = bfs_distances(&grid, [start], |from, to| connected(&grid, from, to));
+            let max = distances.iter().filter_map(|(_, d)| *d).max();
+            assert_eq!(max, Some(farthest));
+            assert_eq!(pipe_loop(&grid).len(), farthest * 2);
+        }
+    }
+
+    #[test]
+    fn finds_enclosed_tiles() {
+        for (map, count) in ENCLOSED {
+            let grid: DenseGrid2D<char> = map.parse().unwrap();
+            let path = pipe_loop(&grid);
+            let enclosed = enclosed_by_loop(grid.width(), grid.height(), &path);
+            assert_eq!(enclosed.len(), count);
+        }
+    }
+
+    #[test]
+    fn measures_regions() {
+        // The enclosed tiles of the first example are two separate pairs
+        let grid: DenseGrid2D<char> = ENCLOSED[0].0.parse().unwrap();
+        let path = pipe_loop(&grid);
+        let mut inside = DenseGrid2D::new(grid.width(), grid.height(), false);
+        for pos in enclosed_by_loop(grid.width(), grid.height(), &path) {
+            inside[pos] = true;
+        }
+        let pockets = regions(&inside, |a, b| a == b)
+            .into_iter()
+            .filter(|region| region.cells.iter().all(|pos| inside[*pos]))
+            .collect::<Vec<_>>();
+        assert_eq!(pockets.len(), 2);
+        for pocket in &pockets {
+            assert_eq!(
+                (pocket.area(), pocket.perimeter(), pocket.sides()),
+                (2, 6, 4)
+            );
+        }
+
+        // The loop, the outside and the two pockets
+        let mut on_loop = DenseGrid2D::new(grid.width(), grid.height(), false);
+        for pos in &path {
+            on_loop[*pos] = true;
+        }
+        let (labels, count) = components(&on_loop, |a, b| a == b);
+        assert_eq!(count, 4);
+        assert!(path.iter().all(|pos| labels[*pos] == labels[path[0]]));
+    }
+
+    #[test]
+    fn counts_sides_with_holes() {
+        let grid: DenseGrid2D<char> = "AAAA\nBBCD\nBBCC\nEEEC\n".parse().unwrap();
+        let shapes = regions(&grid, |a, b| a == b)
+            .iter()
+            .map(|r| (r.area(), r.perimeter(), r.sides()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            shapes,
+            [(4, 10, 4), (4, 8, 4), (4, 10, 8), (1, 4, 4), (3, 8, 4)]
+        );
+
+        let ring = Region::new([
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (0, 1),
+            (2, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+        ]);
+        assert_eq!((ring.area(), ring.perimeter(), ring.sides()), (8, 16, 8));
+    }
+}
@@ -0,0 +1,441 @@
+//! Directions and points.  Like `DenseGrid2D`, `y` grows downwards, so `North` is `(0, -1)`.
+
+use anyhow::{bail, Context, Result};
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir4 {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Dir4 {
+    /// Clockwise from `North`
+    pub const ALL: [Dir4; 4] = [Dir4::North, Dir4::East, Dir4::South, Dir4::West];
+
+    /// Offset of one step, as used by `DenseGrid2D::offset`
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Dir4::North => (0, -1),
+            Dir4::East => (1, 0),
+            Dir4::South => (0, 1),
+            Dir4::West => (-1, 0),
+        }
+    }
+
+    /// Offset of one step as a point
+    pub fn unit<T: From<i8>>(self) -> Point2<T> {
+        let (x, y) = self.delta();
+        Point2::new(T::from(x as i8), T::from(y as i8))
+    }
+
+    pub fn turn_right(self) -> Dir4 {
+        Dir4::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Dir4 {
+        Dir4::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Dir4 {
+        Dir4::ALL[(self as usize + 2) % 4]
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Dir4::East | Dir4::West)
+    }
+}
+
+/// Accepts `U/D/L/R`, `N/S/E/W` and `^v<>`
+impl TryFrom<char> for Dir4 {
+    type Error = anyhow::Error;
+
+    fn try_from(c: char) -> Result<Dir4> {
+        Ok(match c {
+            'U' | 'N' | '^' => Dir4::North,
+            'R' | 'E' | '>' => Dir4::East,
+            'D' | 'S' | 'v' => Dir4::South,
+            'L' | 'W' | '<' => Dir4::West,
+            _ => bail!("Not a direction: {:?}", c),
+        })
+    }
+}
+
+impl FromStr for Dir4 {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Dir4> {
+        let mut chars = s.trim().chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Dir4::try_from(c),
+            _ => bail!("Not a direction: {:?}", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Dir8 {
+    /// Clockwise from `North`
+    pub const ALL: [Dir8; 8] = [
+        Dir8::North,
+        Dir8::NorthEast,
+        Dir8::East,
+        Dir8::SouthEast,
+        Dir8::South,
+        Dir8::SouthWest,
+        Dir8::West,
+        Dir8::NorthWest,
+    ];
+
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Dir8::North => (0, -1),
+            Dir8::NorthEast => (1, -1),
+            Dir8::East => (1, 0),
+            Dir8::SouthEast => (1, 1),
+            Dir8::South => (0, 1),
+            Dir8::SouthWest => (-1, 1),
+            Dir8::West => (-1, 0),
+            Dir8::NorthWest => (-1, -1),
+        }
+    }
+
+    pub fn unit<T: From<i8>>(self) -> Point2<T> {
+        let (x, y) = self.delta();
+        Point2::new(T::from(x as i8), T::from(y as i8))
+    }
+
+    /// An eighth of a turn clockwise
+    pub fn turn_right(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 1) % 8]
+    }
+
+    /// An eighth of a turn counter-clockwise
+    pub fn turn_left(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Dir8 {
+        Dir8::ALL[dir as usize * 2]
+    }
+}
+
+/// Accepts anything `Dir4` does, plus compass points like `NE` and `SW`
+impl FromStr for Dir8 {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Dir8> {
+        Ok(match s.trim() {
+            "NE" => Dir8::NorthEast,
+            "SE" => Dir8::SouthEast,
+            "SW" => Dir8::SouthWest,
+            "NW" => Dir8::NorthWest,
+            other => Dir8::from(other.parse::<Dir4>()?),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// `|a - b|` without requiring a signed type
+fn distance<T: Ord + Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> Point2<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        distance(self.x, other.x) + distance(self.y, other.y)
+    }
+
+    /// Number of king moves between the points
+    pub fn chebyshev(self, other: Self) -> T {
+        distance(self.x, other.x).max(distance(self.y, other.y))
+    }
+}
+
+impl<T: Copy + From<i8> + Add<Output = T> + Mul<Output = T>> Point2<T> {
+    /// The point `n` steps away in `dir`
+    pub fn step(self, dir: Dir4, n: T) -> Self {
+        self + dir.unit::<T>() * n
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> Point3<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        distance(self.x, other.x) + distance(self.y, other.y) + distance(self.z, other.z)
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        distance(self.x, other.x)
+            .max(distance(self.y, other.y))
+            .max(distance(self.z, other.z))
+    }
+}
+
+/// Component-wise operators, and scaling by a scalar
+macro_rules! point_ops {
+    ($point:ident { $($field:ident),+ }) => {
+        impl<T: Add<Output = T>> Add for $point<T> {
+            type Output = Self;
+            fn add(self, other: Self) -> Self {
+                $point { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $point<T> {
+            type Output = Self;
+            fn sub(self, other: Self) -> Self {
+                $point { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl<T: Copy + Mul<Output = T>> Mul<T> for $point<T> {
+            type Output = Self;
+            fn mul(self, scale: T) -> Self {
+                $point { $($field: self.$field * scale),+ }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+            fn neg(self) -> Self {
+                $point { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $point<T> {
+            fn add_assign(&mut self, other: Self) {
+                $(self.$field += other.$field;)+
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: Self) {
+                $(self.$field -= other.$field;)+
+            }
+        }
+    };
+}
+
+point_ops!(Point2 { x, y });
+point_ops!(Point3 { x, y, z });
+
+impl<T: From<i8> + Add<Output = T>> Add<Dir4> for Point2<T> {
+    type Output = Self;
+    fn add(self, dir: Dir4) -> Self {
+        self + dir.unit()
+    }
+}
+
+impl<T: From<i8> + Add<Output = T>> Add<Dir8> for Point2<T> {
+    type Output = Self;
+    fn add(self, dir: Dir8) -> Self {
+        self + dir.unit()
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(point: Point2<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// `N` comma-separated values, e.g. `19, 13, 30`
+fn parse_coords<T, const N: usize>(s: &str) -> Result<[T; N]>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let values = s
+        .split(',')
+        .map(|v| {
+            v.trim()
+                .parse::<T>()
+                .with_context(|| format!("Invalid coordinate {:?}", v.trim()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let len = values.len();
+    values
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Expected {} coordinates in {:?}, found {}", N, s, len))
+}
+
+/// Parses `x,y`
+impl<T> FromStr for Point2<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let [x, y] = parse_coords(s)?;
+        Ok(Point2 { x, y })
+    }
+}
+
+/// Parses `x,y,z`
+impl<T> FromStr for Point3<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let [x, y, z] = parse_coords(s)?;
+        Ok(Point3 { x, y, z })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns() {
+        assert_eq!(Dir4::North.turn_right(), Dir4::East);
+        assert_eq!(Dir4::North.turn_left(), Dir4::West);
+        assert_eq!(Dir4::West.reverse(), Dir4::East);
+        for dir in Dir4::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            let (dx, dy) = dir.delta();
+            assert_eq!(dir.reverse().delta(), (-dx, -dy));
+        }
+        assert_eq!(Dir8::North.turn_left(), Dir8::NorthWest);
+        assert_eq!(Dir8::SouthEast.reverse(), Dir8::NorthWest);
+        assert_eq!(Dir8::from(Dir4::South), Dir8::South);
+        assert!(Dir8::NorthEast.is_diagonal());
+    }
+
+    #[test]
+    fn parses_directions() {
+        let parsed = "U D L R N S E W ^ v < >"
+            .split(' ')
+            .map(|s| s.parse::<Dir4>().unwrap())
+            .collect::<Vec<_>>();
+        use Dir4::*;
+        assert_eq!(
+            parsed,
+            [North, South, West, East, North, South, East, West, North, South, West, East]
+        );
+        assert!("X".parse::<Dir4>().is_err());
+        assert!("UD".parse::<Dir4>().is_err());
+        assert_eq!("SW".parse::<Dir8>().unwrap(), Dir8::SouthWest);
+        assert_eq!("v".parse::<Dir8>().unwrap(), Dir8::South);
+    }
+
+    #[test]
+    fn point_arithmetic() {
+        let p = Point2::new(3i64, -2);
+        assert_eq!(p + Point2::new(1, 1), Point2::new(4, -1));
+        assert_eq!(p - Point2::new(1, 1), Point2::new(2, -3));
+        assert_eq!(p * 2, Point2::new(6, -4));
+        assert_eq!(-p, Point2::new(-3, 2));
+        assert_eq!(p + Dir4::North, Point2::new(3, -3));
+        assert_eq!(p.step(Dir4::East, 5), Point2::new(8, -2));
+        assert_eq!(p + Dir8::SouthWest, Point2::new(2, -1));
+
+        let mut q = p;
+        q += Point2::new(1, 2);
+        q -= Point2::new(0, 1);
+        assert_eq!(q, Point2::new(4, -1));
+
+        assert_eq!(p.manhattan(Point2::new(0, 0)), 5);
+        assert_eq!(p.chebyshev(Point2::new(0, 0)), 3);
+        assert_eq!(Point2::new(1usize, 7).manhattan(Point2::new(4, 2)), 8);
+
+        let a = Point3::new(1, 2, 3);
+        assert_eq!(a + Point3::new(1, 1, 1), Point3::new(2, 3, 4));
+        assert_eq!(a.manhattan(Point3::new(-1, 2, 6)), 5);
+        assert_eq!(a.chebyshev(Point3::new(-1, 2, 6)), 3);
+    }
+
+    #[test]
+    fn parses_points() {
+        assert_eq!("3,4".parse::<Point2<i32>>().unwrap(), Point2::new(3, 4));
+        assert_eq!(
+            "19, 13, -30".parse::<Point3<i64>>().unwrap(),
+            Point3::new(19, 13, -30)
+        );
+        assert!("1,2,3".parse::<Point2<i32>>().is_err());
+        assert!("1,x".parse::<Point2<i32>>().is_err());
+        assert_eq!(Point2::new(1, 2).to_string(), "(1, 2)");
+    }
+}
@@ -0,0 +1,695 @@
+//! A rectangular grid stored row-major in one `Vec`, for the puzzles that hand us a map,
+//! and a sparse one for maps without fixed edges.  Positions are `(x, y)` with `x` the
+//! column and `y` the row, `(0, 0)` being top left.
+
+use crate::geom::{Dir4, Point2};
+use crate::parse::ParseError;
+use anyhow::{bail, Result};
+use nom::Offset;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DenseGrid2D<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+/// Offsets to the four orthogonal neighbours: up, right, down, left
+const NEIGHBORS4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets to all eight neighbours, clockwise from up
+const NEIGHBORS8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+impl<T> DenseGrid2D<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        DenseGrid2D {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// A grid from its cells in row-major order
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Result<Self> {
+        if cells.len() != width * height {
+            bail!(
+                "{} cells don't make a {}x{} grid",
+                cells.len(),
+                width,
+                height
+            );
+        }
+        Ok(DenseGrid2D {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some(y) = rows.iter().position(|row| row.len() != width) {
+            bail!(
+                "Row {} has {} cells, expected {}",
+                y + 1,
+                rows[y].len(),
+                width
+            );
+        }
+        Ok(DenseGrid2D {
+            cells: rows.into_iter().flatten().collect(),
+            width,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if self.contains((x, y)) {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if self.contains((x, y)) {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// Checked lookup for coordinates that may have stepped off the grid
+    pub fn get_signed(&self, x: isize, y: isize) -> Option<&T> {
+        self.get(usize::try_from(x).ok()?, usize::try_from(y).ok()?)
+    }
+
+    /// Lookup on the grid repeated infinitely in every direction, `None` only when the grid
+    /// has no cells to repeat
+    pub fn get_wrapping(&self, x: isize, y: isize) -> Option<&T> {
+        let x = x.checked_rem_euclid(self.width as isize)?;
+        let y = y.checked_rem_euclid(self.height as isize)?;
+        self.get(x as usize, y as usize)
+    }
+
+    /// The position `dx, dy` away from `x, y`, if it is on the grid
+    pub fn offset(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+        self.contains((x, y)).then_some((x, y))
+    }
+
+    /// Up, right, down and left neighbours that are on the grid
+    pub fn neighbors4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBORS4
+            .iter()
+            .filter_map(move |delta| self.offset(pos, *delta))
+    }
+
+    /// Orthogonal and diagonal neighbours that are on the grid
+    pub fn neighbors8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBORS8
+            .iter()
+            .filter_map(move |delta| self.offset(pos, *delta))
+    }
+
+    /// Every position in row-major order
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every cell with its position, in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks() panics on 0, and a zero-width grid has no cells anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Position of the first cell, in row-major order, matching `predicate`
+    pub fn position<P>(&self, mut predicate: P) -> Option<(usize, usize)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    /// Position of the first cell equal to `value`, e.g. the `S` start tile
+    pub fn find(&self, value: &T) -> Option<(usize, usize)>
+    where
+        T: PartialEq,
+    {
+        self.position(|cell| cell == value)
+    }
+
+    pub fn map<U, F>(&self, f: F) -> DenseGrid2D<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        DenseGrid2D {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// A `width` x `height` grid whose cell at `(x, y)` is this grid's cell at `f(x, y)`
+    fn remap<F>(&self, width: usize, height: usize, mut f: F) -> Self
+    where
+        T: Clone,
+        F: FnMut(usize, usize) -> (usize, usize),
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (sx, sy) = f(x, y);
+                self[(sx, sy)].clone()
+            })
+            .collect();
+        DenseGrid2D {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Rows become columns
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |x, y| (y, x))
+    }
+
+    /// Rotated a quarter turn clockwise
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.remap(self.height, self.width, |x, y| (y, height - 1 - x))
+    }
+
+    /// Rotated a quarter turn counter-clockwise
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.remap(self.height, self.width, |x, y| (width - 1 - y, x))
+    }
+
+    /// Mirrored left to right
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.remap(self.width, self.height, |x, y| (width - 1 - x, y))
+    }
+
+    /// Mirrored top to bottom
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.remap(self.width, self.height, |x, y| (x, height - 1 - y))
+    }
+}
+
+impl<T> Index<(usize, usize)> for DenseGrid2D<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            self.contains((x, y)),
+            "({}, {}) is outside the {}x{} grid",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for DenseGrid2D<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(
+            self.contains((x, y)),
+            "({}, {}) is outside the {}x{} grid",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+/// Parses a map with one character per cell, e.g. `char`, `u8` or an enum implementing
+/// `TryFrom<char>`.  Trailing blank lines and `\r` are ignored.
+impl<T> FromStr for DenseGrid2D<T>
+where
+    T: TryFrom<char>,
+    T::Error: fmt::Display,
+{
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lines = s
+            .trim_end()
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .collect::<Vec<_>>();
+        let mut rows = Vec::new();
+        for line in lines {
+            let mut row = Vec::new();
+            for (i, c) in line.char_indices() {
+                match T::try_from(c) {
+                    Ok(cell) => row.push(cell),
+                    Err(e) => {
+                        let message = format!("invalid cell {:?}: {}", c, e);
+                        let offset = s.offset(line) + i;
+                        return Err(ParseError::at(s, offset, c.len_utf8(), message).into());
+                    }
+                }
+            }
+            rows.push(row);
+        }
+        DenseGrid2D::from_rows(rows)
+    }
+}
+
+/// One line per row with the cells written next to each other
+impl<T: fmt::Display> fmt::Display for DenseGrid2D<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> DenseGrid2D<T> {
+    /// The grid repeated infinitely in every direction, for maps that tile.  `None` for a grid
+    /// without cells, which has nothing to repeat.
+    pub fn tiled(&self) -> Option<Tiled<'_, T>> {
+        (self.width > 0 && self.height > 0).then_some(Tiled { grid: self })
+    }
+}
+
+/// Cells at arbitrary signed positions, for maps that grow in every direction.  The
+/// bounding box of the occupied cells is kept up to date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point2<i64>, T>,
+    bounds: Option<(Point2<i64>, Point2<i64>)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid::default()
+    }
+
+    /// The cells of `grid` that match `keep`, e.g. only the walls, at their `(x, y)`
+    pub fn from_dense<F>(grid: &DenseGrid2D<T>, mut keep: F) -> Self
+    where
+        T: Clone,
+        F: FnMut(&T) -> bool,
+    {
+        grid.iter()
+            .filter(|(_, cell)| keep(cell))
+            .map(|((x, y), cell)| (Point2::new(x as i64, y as i64), cell.clone()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, pos: Point2<i64>) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: Point2<i64>) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub fn contains(&self, pos: Point2<i64>) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    pub fn insert(&mut self, pos: Point2<i64>, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (
+                Point2::new(min.x.min(pos.x), min.y.min(pos.y)),
+                Point2::new(max.x.max(pos.x), max.y.max(pos.y)),
+            ),
+            None => (pos, pos),
+        });
+        self.cells.insert(pos, value)
+    }
+
+    pub fn remove(&mut self, pos: Point2<i64>) -> Option<T> {
+        let removed = self.cells.remove(&pos)?;
+        // Only a cell on the edge can shrink the bounding box
+        if let Some((min, max)) = self.bounds {
+            if pos.x == min.x || pos.y == min.y || pos.x == max.x || pos.y == max.y {
+                self.bounds = bounding_box(self.cells.keys().copied());
+            }
+        }
+        Some(removed)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<i64>, &T)> {
+        self.cells.iter().map(|(pos, cell)| (*pos, cell))
+    }
+
+    /// Smallest and largest corner of the occupied cells, both inclusive
+    pub fn bounds(&self) -> Option<(Point2<i64>, Point2<i64>)> {
+        self.bounds
+    }
+
+    /// The bounding box as text, one character per cell from `cell`
+    pub fn render<F>(&self, mut cell: F) -> String
+    where
+        F: FnMut(Option<&T>) -> char,
+    {
+        let mut out = String::new();
+        if let Some((min, max)) = self.bounds {
+            for y in min.y..=max.y {
+                out.extend((min.x..=max.x).map(|x| cell(self.get(Point2::new(x, y)))));
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// A dense grid covering the bounding box, with `fill` for empty cells, and the
+    /// position of its top left cell
+    pub fn to_dense(&self, fill: T) -> (DenseGrid2D<T>, Point2<i64>)
+    where
+        T: Clone,
+    {
+        let Some((min, max)) = self.bounds else {
+            return (DenseGrid2D::new(0, 0, fill), Point2::default());
+        };
+        let width = (max.x - min.x + 1) as usize;
+        let height = (max.y - min.y + 1) as usize;
+        let mut grid = DenseGrid2D::new(width, height, fill);
+        for (pos, cell) in self.iter() {
+            grid[((pos.x - min.x) as usize, (pos.y - min.y) as usize)] = cell.clone();
+        }
+        (grid, min)
+    }
+}
+
+fn bounding_box<I>(positions: I) -> Option<(Point2<i64>, Point2<i64>)>
+where
+    I: Iterator<Item = Point2<i64>>,
+{
+    positions.fold(None, |bounds, pos| match bounds {
+        Some((min, max)) => Some((
+            Point2::new(min.x.min(pos.x), min.y.min(pos.y)),
+            Point2::new(max.x.max(pos.x), max.y.max(pos.y)),
+        )),
+        None => Some((pos, pos)),
+    })
+}
+
+impl<T> FromIterator<(Point2<i64>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point2<i64>, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        for (pos, cell) in iter {
+            grid.insert(pos, cell);
+        }
+        grid
+    }
+}
+
+/// The bounding box with `.` for empty cells
+impl<T: fmt::Display> fmt::Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((min, max)) = self.bounds {
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    match self.get(Point2::new(x, y)) {
+                        Some(cell) => write!(f, "{}", cell)?,
+                        None => write!(f, ".")?,
+                    }
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A dense grid repeated infinitely in every direction, addressed by signed positions.
+/// The grid always has cells, so every position has one.
+#[derive(Debug, Clone, Copy)]
+pub struct Tiled<'a, T> {
+    grid: &'a DenseGrid2D<T>,
+}
+
+impl<'a, T> Tiled<'a, T> {
+    pub fn get(&self, pos: Point2<i64>) -> &'a T {
+        let x = pos.x.rem_euclid(self.grid.width() as i64) as usize;
+        let y = pos.y.rem_euclid(self.grid.height() as i64) as usize;
+        &self.grid[(x, y)]
+    }
+
+    /// Which copy of the grid `pos` falls in, `(0, 0)` being the original
+    pub fn tile(&self, pos: Point2<i64>) -> Point2<i64> {
+        Point2::new(
+            pos.x.div_euclid(self.grid.width() as i64),
+            pos.y.div_euclid(self.grid.height() as i64),
+        )
+    }
+
+    /// The four orthogonal neighbours, which always exist
+    pub fn neighbors4(&self, pos: Point2<i64>) -> impl Iterator<Item = Point2<i64>> {
+        Dir4::ALL.into_iter().map(move |dir| pos + dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The second example of 2023 day 10
+    const PIPES: &str = "..F7.\n.FJ|.\nSJ.L7\n|F--J\nLJ...\n";
+
+    #[test]
+    fn parses_and_displays() {
+        let grid: DenseGrid2D<char> = PIPES.parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (5, 5));
+        assert_eq!(grid.find(&'S'), Some((0, 2)));
+        assert_eq!(grid[(2, 0)], 'F');
+        assert_eq!(grid.to_string(), PIPES);
+
+        let crlf: DenseGrid2D<char> = "ab\r\ncd\r\n\r\n".parse().unwrap();
+        assert_eq!(crlf.to_string(), "ab\ncd\n");
+        assert!("abc\nde".parse::<DenseGrid2D<char>>().is_err());
+
+        let err = "12\n3€".parse::<DenseGrid2D<u8>>().unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Parse error at line 2, column 2: invalid cell '€'"));
+    }
+
+    #[test]
+    fn bounds_and_neighbours() {
+        let grid: DenseGrid2D<char> = PIPES.parse().unwrap();
+        assert_eq!(grid.get(5, 0), None);
+        assert_eq!(grid.get_signed(-1, 0), None);
+        assert_eq!(grid.get_signed(0, 2), Some(&'S'));
+        assert_eq!(grid.get_wrapping(-5, 7), Some(&'S'));
+
+        assert_eq!(
+            grid.neighbors4((0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbors4((2, 2)).count(), 4);
+        assert_eq!(
+            grid.neighbors8((4, 4)).collect::<Vec<_>>(),
+            [(4, 3), (3, 4), (3, 3)]
+        );
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid: DenseGrid2D<char> = "abc\ndef".parse().unwrap();
+        let rows = grid
+            .rows()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(rows, ["abc", "def"]);
+        let columns = grid
+            .columns()
+            .map(|column| column.collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(columns, ["ad", "be", "cf"]);
+        assert_eq!(grid.position(|c| *c > 'c'), Some((0, 1)));
+    }
+
+    #[test]
+    fn transforms() {
+        let grid: DenseGrid2D<char> = "abc\ndef".parse().unwrap();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed\n");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc\n");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.map(|c| c.to_ascii_uppercase()).row(1), ['D', 'E', 'F']);
+    }
+
+    #[test]
+    fn sparse_grid_tracks_bounds() {
+        // The start of the 2023 day 18 dig plan
+        let mut pos = Point2::new(0i64, 0);
+        let mut trench = SparseGrid::new();
+        trench.insert(pos, '#');
+        for (dir, n) in [("R", 6), ("D", 5), ("L", 2), ("D", 2), ("R", 2), ("D", 2)] {
+            let dir: Dir4 = dir.parse().unwrap();
+            for _ in 0..n {
+                pos = pos + dir;
+                trench.insert(pos, '#');
+            }
+        }
+        assert_eq!(
+            trench.bounds(),
+            Some((Point2::new(0, 0), Point2::new(6, 9)))
+        );
+        assert_eq!(trench.len(), 20);
+        assert_eq!(
+            trench.to_string(),
+            "#######\n......#\n......#\n......#\n......#\n....###\n....#..\n....###\n......#\n......#\n"
+        );
+
+        trench.remove(Point2::new(6, 9));
+        assert_eq!(
+            trench.bounds(),
+            Some((Point2::new(0, 0), Point2::new(6, 8)))
+        );
+        trench.insert(Point2::new(-2, -1), '#');
+        assert_eq!(trench.bounds().unwrap().0, Point2::new(-2, -1));
+        assert_eq!(
+            trench
+                .render(|cell| if cell.is_some() { '#' } else { ' ' })
+                .lines()
+                .next(),
+            Some("#        ")
+        );
+    }
+
+    #[test]
+    fn sparse_and_dense_convert() {
+        let dense: DenseGrid2D<char> = ".#.\n..#\n###\n".parse().unwrap();
+        let sparse = SparseGrid::from_dense(&dense, |c| *c == '#');
+        assert_eq!(sparse.len(), 5);
+        assert_eq!(
+            sparse.bounds(),
+            Some((Point2::new(0, 0), Point2::new(2, 2)))
+        );
+        let (back, origin) = sparse.to_dense('.');
+        assert_eq!(back, dense);
+        assert_eq!(origin, Point2::new(0, 0));
+
+        let shifted: SparseGrid<char> = [(Point2::new(-3, 5), 'a'), (Point2::new(-1, 6), 'b')]
+            .into_iter()
+            .collect();
+        let (dense, origin) = shifted.to_dense(' ');
+        assert_eq!(dense.to_string(), "a  \n  b\n");
+        assert_eq!(origin, Point2::new(-3, 5));
+    }
+
+    #[test]
+    fn tiled_view_wraps() {
+        let garden: DenseGrid2D<char> = "...\n.S#\n...\n".parse().unwrap();
+        let tiled = garden.tiled().unwrap();
+        assert_eq!(*tiled.get(Point2::new(1, 1)), 'S');
+        assert_eq!(*tiled.get(Point2::new(-2, 4)), 'S');
+        assert_eq!(*tiled.get(Point2::new(-1, -2)), '#');
+        assert_eq!(tiled.tile(Point2::new(-1, 3)), Point2::new(-1, 1));
+        assert_eq!(tiled.neighbors4(Point2::new(0, 0)).count(), 4);
+    }
+
+    #[test]
+    fn handles_empty_grids() {
+        for (width, height) in [(0, 3), (3, 0), (0, 0)] {
+            let grid = DenseGrid2D::new(width, height, '.');
+            assert_eq!(grid.get_wrapping(-1, 1), None);
+            assert!(grid.tiled().is_none());
+        }
+    }
+}
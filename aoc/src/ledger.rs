@@ -0,0 +1,408 @@
+//! Answers we know to be right or wrong, kept next to the cached input as
+//! `<year>/<day>.answers` with one `part<TAB>verdict<TAB>answer` line per submitted answer.
+//! A `wait<TAB>unix-seconds` line records the cooldown the server last imposed.
+
+use crate::client::{Client, Verdict};
+use crate::{Config, Puzzle};
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+}
+
+impl Status {
+    fn name(&self) -> &'static str {
+        match self {
+            Status::Correct => "correct",
+            Status::Wrong => "wrong",
+            Status::TooHigh => "too-high",
+            Status::TooLow => "too-low",
+        }
+    }
+
+    fn from_verdict(verdict: &Verdict) -> Option<Status> {
+        match verdict {
+            Verdict::Correct => Some(Status::Correct),
+            Verdict::Wrong => Some(Status::Wrong),
+            Verdict::TooHigh => Some(Status::TooHigh),
+            Verdict::TooLow => Some(Status::TooLow),
+            Verdict::TooSoon | Verdict::Other(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub part: u8,
+    pub status: Status,
+    pub answer: String,
+}
+
+#[derive(Debug)]
+pub struct Ledger {
+    path: PathBuf,
+    entries: Vec<Entry>,
+    wait_until: Option<SystemTime>,
+}
+
+impl Ledger {
+    pub fn for_puzzle(config: &Config, puzzle: &Puzzle) -> Result<Ledger> {
+        Ledger::load(&config.ledger_path(puzzle))
+    }
+
+    /// Read a ledger, starting an empty one if the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Ledger> {
+        let mut ledger = Ledger {
+            path: path.to_path_buf(),
+            entries: Vec::new(),
+            wait_until: None,
+        };
+        if path.exists() {
+            let contents = std::fs::read_to_string(path)?;
+            for (i, line) in contents.lines().enumerate() {
+                ledger.parse_line(line).with_context(|| {
+                    format!("{}:{}: bad entry {:?}", path.display(), i + 1, line)
+                })?;
+            }
+        }
+        Ok(ledger)
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<()> {
+        if let Some(secs) = line.strip_prefix("wait\t") {
+            let secs = secs.parse::<u64>().context("expected unix seconds")?;
+            self.wait_until = Some(UNIX_EPOCH + Duration::from_secs(secs));
+        } else {
+            self.entries.push(parse_entry(line)?);
+        }
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn correct(&self, part: u8) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|e| e.part == part && e.status == Status::Correct)
+            .map(|e| e.answer.as_str())
+    }
+
+    pub fn is_wrong(&self, part: u8, answer: &str) -> bool {
+        self.entries
+            .iter()
+            .any(|e| e.part == part && e.status != Status::Correct && e.answer == answer)
+    }
+
+    /// Add an answer and write the ledger back out
+    pub fn record(&mut self, part: u8, status: Status, answer: &str) -> Result<()> {
+        let entry = Entry {
+            part,
+            status,
+            answer: answer.to_string(),
+        };
+        if self.entries.contains(&entry) {
+            return Ok(());
+        }
+        self.entries.push(entry);
+        self.save()
+    }
+
+    pub fn set_wait_until(&mut self, until: SystemTime) -> Result<()> {
+        self.wait_until = Some(until);
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut contents = String::new();
+        if let Some(until) = self.wait_until {
+            let secs = until.duration_since(UNIX_EPOCH)?.as_secs();
+            contents.push_str(&format!("wait\t{}\n", secs));
+        }
+        for e in &self.entries {
+            contents.push_str(&format!(
+                "part{}\t{}\t{}\n",
+                e.part,
+                e.status.name(),
+                e.answer
+            ));
+        }
+        std::fs::write(&self.path, contents)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    /// Fail if `answer` contradicts what the ledger knows about `part`
+    pub fn check(&self, part: u8, answer: &str) -> Result<()> {
+        if let Some(correct) = self.correct(part) {
+            if correct != answer {
+                bail!(
+                    "Part {} answer changed: got {} but {} was accepted",
+                    part,
+                    answer,
+                    correct
+                );
+            }
+        } else if self.is_wrong(part, answer) {
+            bail!("Part {} answer {} is known to be wrong", part, answer);
+        }
+        Ok(())
+    }
+
+    /// Why `answer` shouldn't be submitted for `part` at time `now`, if there is a reason
+    pub fn refusal(&self, part: u8, answer: &str, now: SystemTime) -> Option<String> {
+        if let Some(correct) = self.correct(part) {
+            return Some(format!("part {} was already solved with {}", part, correct));
+        }
+        if let Some(e) = self
+            .entries
+            .iter()
+            .find(|e| e.part == part && e.answer == answer)
+        {
+            return Some(format!(
+                "{} was already submitted ({})",
+                answer,
+                e.status.name()
+            ));
+        }
+        if let Ok(value) = answer.parse::<i128>() {
+            let bound = |status: Status| {
+                self.entries
+                    .iter()
+                    .filter(move |e| e.part == part && e.status == status)
+                    .filter_map(|e| e.answer.parse::<i128>().ok())
+            };
+            if let Some(high) = bound(Status::TooHigh).min() {
+                if value >= high {
+                    return Some(format!(
+                        "{} is not below {}, which was too high",
+                        value, high
+                    ));
+                }
+            }
+            if let Some(low) = bound(Status::TooLow).max() {
+                if value <= low {
+                    return Some(format!("{} is not above {}, which was too low", value, low));
+                }
+            }
+        }
+        if let Some(until) = self.wait_until {
+            if let Ok(remaining) = until.duration_since(now) {
+                return Some(format!(
+                    "the server asked us to wait another {}s",
+                    remaining.as_secs()
+                ));
+            }
+        }
+        None
+    }
+
+    /// Submit `answer` unless the ledger rules it out, then record the verdict and any cooldown
+    pub fn submit(
+        &mut self,
+        client: &Client,
+        puzzle: &Puzzle,
+        answer: &str,
+        now: SystemTime,
+    ) -> Result<Verdict> {
+        if let Some(reason) = self.refusal(puzzle.part, answer, now) {
+            bail!("Not submitting: {}", reason);
+        }
+
+        let response = client.submit(puzzle.year, puzzle.day, puzzle.part, answer)?;
+        if let Some(wait) = response.wait {
+            self.set_wait_until(now + wait)?;
+        }
+        if let Some(status) = Status::from_verdict(&response.verdict) {
+            self.record(puzzle.part, status, answer)?;
+        }
+        Ok(response.verdict)
+    }
+}
+
+fn parse_entry(line: &str) -> Result<Entry> {
+    let mut fields = line.splitn(3, '\t');
+    let (part, status, answer) = match (fields.next(), fields.next(), fields.next()) {
+        (Some(part), Some(status), Some(answer)) => (part, status, answer),
+        _ => bail!("expected part, status and answer separated by tabs"),
+    };
+    let part = part
+        .strip_prefix("part")
+        .and_then(|p| p.parse::<u8>().ok())
+        .context("expected part1 or part2")?;
+    let status = [
+        Status::Correct,
+        Status::Wrong,
+        Status::TooHigh,
+        Status::TooLow,
+    ]
+    .into_iter()
+    .find(|s| s.name() == status)
+    .with_context(|| format!("unknown status {}", status))?;
+    Ok(Entry {
+        part,
+        status,
+        answer: answer.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    fn temp_ledger(name: &str) -> Ledger {
+        let path = std::env::temp_dir().join(format!(
+            "aoc-ledger-{}-{}.answers",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        Ledger::load(&path).unwrap()
+    }
+
+    /// Serve one canned HTML page per connection, sending each request body back over the channel
+    fn mock_server(pages: Vec<&'static str>) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for page in pages {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                sender.send(String::from_utf8(body).unwrap()).unwrap();
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    page.len(),
+                    page
+                );
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            }
+        });
+        (url, receiver)
+    }
+
+    #[test]
+    fn records_and_checks_answers() {
+        let mut ledger = temp_ledger("check");
+        assert!(ledger.check(1, "42").is_ok());
+        ledger.record(1, Status::Wrong, "41").unwrap();
+        ledger.record(1, Status::Correct, "42").unwrap();
+        ledger.record(1, Status::Correct, "42").unwrap();
+
+        let ledger = Ledger::load(&ledger.path).unwrap();
+        assert_eq!(ledger.entries().len(), 2);
+        assert_eq!(ledger.correct(1), Some("42"));
+        assert_eq!(ledger.correct(2), None);
+        assert!(ledger.check(1, "42").is_ok());
+        assert_eq!(
+            ledger.check(1, "43").unwrap_err().to_string(),
+            "Part 1 answer changed: got 43 but 42 was accepted"
+        );
+        assert!(ledger.is_wrong(1, "41"));
+
+        std::fs::remove_file(&ledger.path).unwrap();
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert!(parse_entry("part1\tcorrect\t1,2").is_ok());
+        assert!(parse_entry("part2\ttoo-low\t12").is_ok());
+        assert!(parse_entry("part1 correct 12").is_err());
+        assert!(parse_entry("partx\tcorrect\t12").is_err());
+    }
+
+    #[test]
+    fn guards_submissions() {
+        let (url, requests) = mock_server(vec![
+            "<article><p>That's not the right answer; your answer is too high.  please wait one minute before trying again.</p></article>",
+            "<article><p>That's not the right answer; your answer is too low.  please wait one minute before trying again.</p></article>",
+            "<article><p>That's the right answer!</p></article>",
+        ]);
+        let client = Client::with_base_url(&url, "token");
+        let puzzle = Puzzle {
+            year: 2023,
+            day: 9,
+            part: 1,
+        };
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let later = |secs| start + Duration::from_secs(secs);
+        let mut ledger = temp_ledger("guard");
+
+        assert_eq!(
+            ledger.submit(&client, &puzzle, "100", start).unwrap(),
+            Verdict::TooHigh
+        );
+        assert_eq!(requests.recv().unwrap(), "level=1&answer=100");
+
+        // Refused locally without contacting the server
+        let refused = |ledger: &mut Ledger, answer, now| {
+            ledger
+                .submit(&client, &puzzle, answer, now)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            refused(&mut ledger, "50", later(30)),
+            "Not submitting: the server asked us to wait another 30s"
+        );
+        assert_eq!(
+            refused(&mut ledger, "100", later(120)),
+            "Not submitting: 100 was already submitted (too-high)"
+        );
+        assert_eq!(
+            refused(&mut ledger, "150", later(120)),
+            "Not submitting: 150 is not below 100, which was too high"
+        );
+
+        assert_eq!(
+            ledger.submit(&client, &puzzle, "10", later(120)).unwrap(),
+            Verdict::TooLow
+        );
+        assert_eq!(
+            refused(&mut ledger, "5", later(240)),
+            "Not submitting: 5 is not above 10, which was too low"
+        );
+        assert_eq!(
+            ledger.submit(&client, &puzzle, "50", later(240)).unwrap(),
+            Verdict::Correct
+        );
+        assert_eq!(
+            refused(&mut ledger, "51", later(360)),
+            "Not submitting: part 1 was already solved with 50"
+        );
+        assert_eq!(requests.iter().count(), 2);
+
+        // Everything, including the cooldown, survives a reload
+        let reloaded = Ledger::load(&ledger.path).unwrap();
+        assert_eq!(reloaded.entries(), ledger.entries());
+        assert_eq!(reloaded.wait_until, Some(later(180)));
+
+        std::fs::remove_file(&ledger.path).unwrap();
+    }
+}
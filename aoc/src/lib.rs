@@ -1,16 +1,67 @@
-use anyhow::{Context, Result};
-pub use aoc_macro::main;
+use anyhow::{bail, Context, Result};
+pub use aoc_macro::{main, AocParse};
+pub use bitgrid::BitGrid;
 use clap::Parser;
+pub use client::Verdict;
+pub use config::{Config, ConfigArgs};
+pub use examples::{run_examples, Example};
+pub use grid::{DenseGrid2D, SparseGrid};
+pub use ledger::Ledger;
+pub use parse::{ints, ints_fixed, uints};
+pub use runner::{finish_script, run_profiles, PartRun, Solution, SolutionRun};
+pub use timing::{time, Timing};
 // use regex::Regex;
 use std::fmt::Display;
 use std::str::FromStr;
 use std::vec::Vec;
 
+pub mod bitgrid;
+pub mod client;
+#[cfg(doctest)]
+mod compile_fail;
+pub mod config;
+pub mod cycle;
+pub mod description;
+mod examples;
+pub mod flood;
+pub mod geom;
+pub mod grid;
+pub mod ledger;
+pub mod math;
+pub mod parse;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+mod timing;
+
 /// Arguments
 #[derive(Parser)]
 pub struct Cli {
     #[arg(long)]
     pub submit: bool,
+
+    /// Only run (and submit) the given part
+    #[arg(long)]
+    pub part: Option<u8>,
+
+    /// Run parsing and solving this many times and report min/median/max
+    #[arg(long, default_value_t = 1)]
+    pub repeat: usize,
+
+    /// Fail if an answer differs from one already accepted
+    #[arg(long)]
+    pub check: bool,
+
+    /// Read the input from this file ("-" for stdin) instead of the cache
+    #[arg(long)]
+    pub input: Option<std::path::PathBuf>,
+
+    /// Run on the cached input of every profile and tabulate the answers
+    #[arg(long, conflicts_with_all = ["input", "submit", "profile"])]
+    pub all_profiles: bool,
+
+    #[command(flatten)]
+    pub config: ConfigArgs,
 }
 
 // Define our error types. These may be customized for our error handling cases.
@@ -19,22 +70,6 @@ pub struct Cli {
 #[derive(Debug, Clone)]
 pub struct NoSolutionError;
 
-// pub struct DenseGrid2D<T> {
-//     cells: Vec<Vec<T>>,
-//     rows: usize,
-//     cols: usize,
-// }
-
-// impl<T> DenseGrid2D<T> {
-//     fn get(&self, x: &usize, y: &usize) -> Option<&T> {
-//         if *x >= self.cols || *y >= self.rows {
-//             None
-//         } else {
-//             Some(&self.cells[*y][*x])
-//         }
-//     }
-// }
-
 // Add regex methods to ToString types
 pub trait Searchable {
     fn rematch(&self, needle: &str) -> bool;
@@ -64,37 +99,9 @@ pub trait Searchable {
 //     }
 // }
 
+/// Least common multiple of `numbers`, see `math::lcm_all`.  Panics if it overflows.
 pub fn lcm(numbers: Vec<u64>) -> u64 {
-    let mut temp = numbers.clone();
-
-    // check all the same
-    loop {
-        let mut same = true;
-
-        for idx in 1..temp.len() {
-            if temp[0] != temp[idx] {
-                same = false;
-                break;
-            }
-        }
-
-        if same {
-            return temp[0];
-        }
-
-        // Find lowest index
-        match temp
-            .iter()
-            .enumerate()
-            .min_by(|(_, a), (_, b)| a.cmp(b))
-            .map(|(index, _)| index)
-        {
-            Some(idx) => {
-                temp[idx] += numbers[idx];
-            }
-            None => panic!("Not possible"),
-        }
-    }
+    math::lcm_all(numbers).expect("lcm overflows u64")
 }
 
 // Generation of an error is completely separate from how it is displayed.
@@ -113,102 +120,83 @@ pub fn get_cli_args() -> Cli {
 }
 
 pub fn parse_u32_list(input: &str) -> Result<Vec<u32>> {
-    let vals: Result<Vec<_>, _> = input.lines().map(|val| val.parse::<u32>()).collect();
-    Ok(vals?)
+    parse_list(input)
 }
 
-pub fn parse_list<T>(input: &str) -> std::result::Result<Vec<T>, <T as FromStr>::Err>
+/// One value per line, failing with a `parse::ParseError` pointing at the first bad line
+pub fn parse_list<T>(input: &str) -> Result<Vec<T>>
 where
     T: FromStr,
+    T::Err: Display,
 {
-    let vals: Result<Vec<_>, _> = input.lines().map(|val| val.parse::<T>()).collect();
-    vals
+    parse::parse_lines(input, input)
 }
 
-pub fn parse_grouped_list<T>(input: &str) -> std::result::Result<Vec<Vec<T>>, <T as FromStr>::Err>
+/// One list per blank-line separated group of lines, see `parse::split_sections`
+pub fn parse_grouped_list<T>(input: &str) -> Result<Vec<Vec<T>>>
 where
     T: FromStr,
+    T::Err: Display,
 {
-    let mut lists = Vec::new();
-    let mut last_group = Vec::new();
-    for line in input.lines() {
-        if line.is_empty() && !last_group.is_empty() {
-            lists.push(last_group);
-            last_group = Vec::new();
-        } else {
-            last_group.push(line.parse::<T>()?);
+    parse::split_sections(input)
+        .into_iter()
+        .map(|section| parse::parse_lines(input, section))
+        .collect()
+}
+
+/// Submit an answer, unless the puzzle's answer ledger rules it out, and record the verdict
+pub fn submit(config: &Config, puzzle: &Puzzle, answer: &str) -> Result<()> {
+    let client = client::Client::new(&client::session_token(config)?);
+    let mut ledger = Ledger::for_puzzle(config, puzzle)?;
+    let verdict = ledger.submit(&client, puzzle, answer, std::time::SystemTime::now())?;
+    println!("Verdict: {}", verdict);
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Puzzle {
+    pub day: usize,
+    pub year: usize,
+    pub part: u8,
+}
+
+/// Input for a solution binary: the `--input` file or stdin if given, otherwise the cached input
+pub fn load_input(puzzle: &Puzzle, args: &Cli) -> Result<String> {
+    match &args.input {
+        Some(file) if file.as_os_str() == "-" => {
+            let mut input = String::new();
+            std::io::Read::read_to_string(&mut std::io::stdin(), &mut input)?;
+            Ok(input)
         }
+        Some(file) => std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to read input {}", file.display())),
+        None => get_input_for_puzzle(&Config::from_args(&args.config)?, puzzle),
     }
-    if !last_group.is_empty() {
-        lists.push(last_group);
-    }
-    Ok(lists)
-}
-
-fn get_input(day: usize, year: usize) -> Result<String> {
-    elv::get_input(day, year, None)
-}
-
-pub fn submit_script<T>(path: &str, answer: &T) -> Result<()>
-where
-    T: Display + ?Sized,
-{
-    let puzzle = puzzle_from_file_name(path)?;
-    submit(puzzle.day, puzzle.year, &answer.to_string(), puzzle.part)
-}
-
-fn submit(day: usize, year: usize, answer: &str, part: u8) -> Result<()> {
-    elv::submit(day, year, answer, part, None)
-}
-
-struct Puzzle {
-    day: usize,
-    year: usize,
-    part: u8,
-}
-
-fn puzzle_from_file_name(path: &str) -> Result<Puzzle> {
-    let (_, year, day, part) =
-        lazy_regex::regex_captures!(r#"/(\d{4})/src/bin/day(\d+)(?:_part(\d))?.*.rs$"#, path)
-            .context("File must be named YYYY/src/bin/day\\d+.rs")?;
-    let day = day
-        .parse::<usize>()
-        .with_context(|| format!("Failed to parse day, {}", day))?;
-    let year = year
-        .parse::<usize>()
-        .with_context(|| format!("Failed to parse year, {}", year))?;
-
-    let part = if !part.is_empty() {
-        part.parse::<u8>()
-            .with_context(|| format!("Failed to parse part, {}", part))?
-    } else {
-        1
-    };
-
-    Ok(Puzzle { day, year, part })
 }
 
-pub fn get_input_for_script(path: &str) -> Result<String> {
-    let puzzle = puzzle_from_file_name(path)?;
-
-    let home_dir = std::env::var("HOME")?;
-    let cache_fn = format!("{}/.aoc/{}/{}.inp", home_dir, puzzle.year, puzzle.day);
-    println!("Cache file={}", cache_fn);
-    let input_cache_path = std::path::Path::new(&cache_fn);
+pub fn get_input_for_puzzle(config: &Config, puzzle: &Puzzle) -> Result<String> {
+    let input_cache_path = config.input_path(puzzle);
     if !input_cache_path.exists() {
-        // Create parent folder
-        println!("Creating {:?}", input_cache_path.parent().unwrap());
-        std::fs::create_dir_all(input_cache_path.parent().unwrap()).unwrap();
+        if config.offline {
+            bail!(
+                "Input for {} day {} is not cached at {} and offline mode is on",
+                puzzle.year,
+                puzzle.day,
+                input_cache_path.display()
+            );
+        }
 
         // Download and save the data
-        let input = get_input(puzzle.day, puzzle.year)?;
-        std::fs::File::create(input_cache_path)?;
-        std::fs::write(input_cache_path, input)?;
+        println!("Downloading input to {}", input_cache_path.display());
+        std::fs::create_dir_all(config.puzzle_dir(puzzle))?;
+        let client = client::Client::new(&client::session_token(config)?);
+        let input = client.input(puzzle.year, puzzle.day)?;
+        std::fs::write(&input_cache_path, input)?;
     }
 
     // Slurp the cache file
-    let input = std::fs::read_to_string(input_cache_path)?;
-    Ok(input)
+    std::fs::read_to_string(&input_cache_path)
+        .with_context(|| format!("Failed to read {}", input_cache_path.display()))
 }
 
 pub fn add(left: usize, right: usize) -> usize {
@@ -224,4 +212,22 @@ mod tests {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn groups_ignore_extra_blank_lines() {
+        let groups = parse_grouped_list::<u32>("\n1000\r\n2000\r\n\r\n\r\n4000\n").unwrap();
+        assert_eq!(groups, [vec![1000, 2000], vec![4000]]);
+    }
+
+    #[test]
+    fn list_errors_point_at_the_line() {
+        let err = parse_grouped_list::<u32>("1\n2\n\n3\nfour\n").unwrap_err();
+        let parse_error = parse::find_parse_error(&err).unwrap();
+        assert_eq!((parse_error.line, parse_error.column), (5, 1));
+        assert_eq!(
+            parse_error.snippet(),
+            " --> line 5, column 1\n  |\n5 | four\n  | ^^^^ invalid digit found in string\n"
+        );
+        assert!(parse_list::<i64>("1\n-2\n").is_ok());
+    }
 }
@@ -0,0 +1,202 @@
+//! Number theory for puzzles whose answer is when several cycles line up: gcd and lcm over
+//! any primitive integer, extended Euclid, modular inverses and the Chinese Remainder
+//! Theorem.  Anything that could overflow is checked.
+
+use std::fmt::Debug;
+use std::ops::{Div, Rem};
+
+pub mod poly;
+
+/// The primitive integers, signed and unsigned, up to 128 bits
+pub trait Integer: Copy + Ord + Debug + Div<Output = Self> + Rem<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    /// The remainder, 0 for `MIN % -1` where `%` would overflow
+    fn wrapping_rem(self, other: Self) -> Self;
+
+    /// The absolute value, `None` if it doesn't fit (`MIN` of a signed type)
+    fn checked_abs(self) -> Option<Self>;
+}
+
+macro_rules! integer {
+    (signed: $($t:ty),*) => {
+        $(impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn wrapping_rem(self, other: Self) -> Self {
+                <$t>::wrapping_rem(self, other)
+            }
+
+            fn checked_abs(self) -> Option<Self> {
+                <$t>::checked_abs(self)
+            }
+        })*
+    };
+    (unsigned: $($t:ty),*) => {
+        $(impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn wrapping_rem(self, other: Self) -> Self {
+                <$t>::wrapping_rem(self, other)
+            }
+
+            fn checked_abs(self) -> Option<Self> {
+                Some(self)
+            }
+        })*
+    };
+}
+
+integer!(signed: i8, i16, i32, i64, i128, isize);
+integer!(unsigned: u8, u16, u32, u64, u128, usize);
+
+/// Greatest common divisor, never negative, and `gcd(0, 0) == 0`.  `None` if it doesn't
+/// fit, which only happens for `gcd(MIN, 0)` and `gcd(MIN, MIN)` of a signed type.
+pub fn gcd<T: Integer>(mut a: T, mut b: T) -> Option<T> {
+    while b != T::ZERO {
+        (a, b) = (b, a.wrapping_rem(b));
+    }
+    a.checked_abs()
+}
+
+/// Least common multiple, never negative, or `None` if it overflows `T`
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a.checked_abs()? / gcd(a, b)?).checked_mul(b.checked_abs()?)
+}
+
+/// Least common multiple of all of `numbers`, 1 if there are none
+pub fn lcm_all<T, I>(numbers: I) -> Option<T>
+where
+    T: Integer,
+    I: IntoIterator<Item = T>,
+{
+    numbers.into_iter().try_fold(T::ONE, |acc, n| lcm(acc, n))
+}
+
+/// `(g, x, y)` with `g = gcd(a, b)` and `a * x + b * y == g`
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    assert!(m > 0, "modulus must be positive, not {}", m);
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// The `x` satisfying every `x ≡ residue (mod modulus)` in `congruences`, as the smallest
+/// non-negative solution and the modulus all solutions repeat with (the lcm of the moduli).
+/// The moduli need not be coprime; `None` means the congruences contradict each other.
+/// Panics if the combined modulus overflows `i128`.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut x = 0;
+    let mut m = 1;
+    for &(residue, modulus) in congruences {
+        assert!(modulus > 0, "modulus must be positive, not {}", modulus);
+        // x + m * k ≡ residue (mod modulus), solved for k
+        let g = gcd(m, modulus).expect("both moduli are positive");
+        let diff = residue - x;
+        if diff.rem_euclid(g) != 0 {
+            return None;
+        }
+        let step = modulus / g;
+        let inverse = mod_inverse(m / g, step).expect("m / g and modulus / g are coprime");
+        let k = ((diff / g).rem_euclid(step))
+            .checked_mul(inverse)
+            .expect("CRT intermediate overflows i128")
+            % step;
+        let combined = lcm(m, modulus).expect("CRT modulus overflows i128");
+        x = (x + m * k).rem_euclid(combined);
+        m = combined;
+    }
+    Some((x, m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12u32, 18), Some(6));
+        assert_eq!(gcd(-12i64, 18), Some(6));
+        assert_eq!(gcd(0u8, 0), Some(0));
+        assert_eq!(gcd(i64::MIN, -1), Some(1));
+        assert_eq!(gcd(i64::MIN, 6), Some(2));
+        assert_eq!(gcd(i8::MIN, i8::MIN), None);
+        assert_eq!(gcd(0, i128::MIN), None);
+        assert_eq!(lcm(i64::MIN, -1), None);
+        assert_eq!(lcm(i64::MIN, 2), None);
+        assert_eq!(lcm(4i32, -6), Some(12));
+        assert_eq!(lcm(0u64, 7), Some(0));
+        assert_eq!(lcm(200u8, 3), None);
+        assert_eq!(lcm(u128::MAX / 3, 3), Some(u128::MAX));
+        assert_eq!(lcm_all(Vec::<usize>::new()), Some(1));
+
+        // 2023 day 8: each ghost's cycle is the shared instruction count times a prime
+        let ghosts = [11309u64, 13939, 15517, 17621, 18673, 20777];
+        assert_eq!(lcm_all(ghosts), Some(13289612809129));
+        assert_eq!(crate::lcm(ghosts.to_vec()), 13289612809129);
+        // 2023 day 20: the counters feeding `rx` have prime periods
+        assert_eq!(lcm_all([3739u64, 3761, 3797, 3889]), Some(207652583562007));
+        assert_eq!(lcm_all([3u64, u64::MAX - 1]), None);
+    }
+
+    #[test]
+    fn inverses() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(extended_gcd(-4, 6).0, 2);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Not coprime but consistent, then contradictory
+        assert_eq!(crt(&[(2, 6), (8, 9)]), Some((8, 18)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+
+        // 2020 day 13: bus `id` at offset `i` departs at t + i
+        let buses = "7,13,x,x,59,x,31,19"
+            .split(',')
+            .enumerate()
+            .filter_map(|(i, id)| id.parse::<i128>().ok().map(|id| (-(i as i128), id)))
+            .collect::<Vec<_>>();
+        assert_eq!(crt(&buses), Some((1068781, 7 * 13 * 59 * 31 * 19)));
+    }
+}
@@ -0,0 +1,282 @@
+//! Sequences that follow a polynomial: difference tables, extrapolation either way, the
+//! value at a far-off index, and exact Lagrange interpolation through arbitrary points.
+
+use super::gcd;
+use std::fmt;
+
+/// `values` and the rows of differences below it, down to a row of zeros or a single value,
+/// or `None` if a difference overflows `i64`
+pub fn difference_table(values: &[i64]) -> Option<Vec<Vec<i64>>> {
+    let mut table = vec![values.to_vec()];
+    loop {
+        let row = table.last().unwrap();
+        if row.len() <= 1 || row.iter().all(|v| *v == 0) {
+            return Some(table);
+        }
+        let next = row
+            .windows(2)
+            .map(|w| w[1].checked_sub(w[0]))
+            .collect::<Option<_>>()?;
+        table.push(next);
+    }
+}
+
+/// The value after the last of `values`, `None` if it or a difference overflows `i64`
+pub fn next_value(values: &[i64]) -> Option<i64> {
+    difference_table(values)?
+        .iter()
+        .filter_map(|row| row.last())
+        .try_fold(0i64, |sum, last| sum.checked_add(*last))
+}
+
+/// The value before the first of `values`, `None` if it or a difference overflows `i64`
+pub fn previous_value(values: &[i64]) -> Option<i64> {
+    difference_table(values)?
+        .iter()
+        .rev()
+        .filter_map(|row| row.first())
+        .try_fold(0i64, |below, first| first.checked_sub(below))
+}
+
+/// The value at index `n` of the sequence starting with `values`, which may be far beyond
+/// them or negative, taking the lowest degree polynomial through them (0 everywhere if
+/// `values` is empty).  Sums binomial coefficients times the leading differences in
+/// `i128`: `None` if any of those overflows, or a difference overflows `i64`, which can
+/// happen even when the answer fits.
+pub fn value_at(values: &[i64], n: i128) -> Option<i128> {
+    let mut total: i128 = 0;
+    // C(n, k), exact at every step since the product of k consecutive integers divides by k!
+    let mut binomial: i128 = 1;
+    for (k, row) in difference_table(values)?.iter().enumerate() {
+        // Only the first row can be empty, when there are no values
+        let Some(&leading) = row.first() else {
+            break;
+        };
+        let k = k as i128;
+        if k > 0 {
+            binomial = binomial.checked_mul(n.checked_sub(k - 1)?)? / k;
+        }
+        total = total.checked_add(binomial.checked_mul(leading as i128)?)?;
+    }
+    Some(total)
+}
+
+/// An exact fraction in lowest terms with a positive denominator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ratio {
+    numer: i128,
+    denom: i128,
+}
+
+impl Ratio {
+    /// Panics if `denom` is zero, or if the fraction doesn't fit `i128` in lowest terms
+    /// with a positive denominator, as with `i128::MIN / -1`
+    pub fn new(numer: i128, denom: i128) -> Self {
+        assert!(denom != 0, "{}/0 is not a number", numer);
+        Ratio::checked_new(numer, denom)
+            .unwrap_or_else(|| panic!("{}/{} doesn't fit i128", numer, denom))
+    }
+
+    /// `new` for a non-zero `denom`, `None` if the result doesn't fit
+    fn checked_new(numer: i128, denom: i128) -> Option<Self> {
+        let g = gcd(numer, denom)?;
+        let sign = denom.signum();
+        Some(Ratio {
+            numer: (numer / g).checked_mul(sign)?,
+            denom: (denom / g).checked_mul(sign)?,
+        })
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.numer
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.denom
+    }
+
+    /// The value if it is a whole number
+    pub fn to_integer(&self) -> Option<i128> {
+        (self.denom == 1).then_some(self.numer)
+    }
+
+    pub fn checked_add(self, other: Ratio) -> Option<Ratio> {
+        let g = gcd(self.denom, other.denom)?;
+        let denom = (self.denom / g).checked_mul(other.denom)?;
+        let numer = self
+            .numer
+            .checked_mul(other.denom / g)?
+            .checked_add(other.numer.checked_mul(self.denom / g)?)?;
+        Ratio::checked_new(numer, denom)
+    }
+
+    pub fn checked_mul(self, other: Ratio) -> Option<Ratio> {
+        // Cancel across first so the products stay as small as possible
+        let a = gcd(self.numer, other.denom)?;
+        let b = gcd(other.numer, self.denom)?;
+        let numer = (self.numer / a).checked_mul(other.numer / b)?;
+        let denom = (self.denom / b).checked_mul(other.denom / a)?;
+        Ratio::checked_new(numer, denom)
+    }
+}
+
+impl From<i128> for Ratio {
+    fn from(n: i128) -> Self {
+        Ratio { numer: n, denom: 1 }
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.denom == 1 {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+/// The value at `x` of the lowest degree polynomial through `points`, exactly, or `None`
+/// if it overflows `i128`.  Panics if two points share an `x`.
+pub fn lagrange(points: &[(i128, i128)], x: i128) -> Option<Ratio> {
+    let mut total = Ratio::from(0);
+    for (i, &(xi, yi)) in points.iter().enumerate() {
+        let mut term = Ratio::from(yi);
+        for (j, &(xj, _)) in points.iter().enumerate() {
+            if i != j {
+                assert!(xi != xj, "two points at x = {}", xi);
+                let factor = Ratio::checked_new(x.checked_sub(xj)?, xi.checked_sub(xj)?)?;
+                term = term.checked_mul(factor)?;
+            }
+        }
+        total = total.checked_add(term)?;
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::Point2;
+    use crate::DenseGrid2D;
+    use std::collections::HashSet;
+
+    #[test]
+    fn extrapolates_sequences() {
+        // 2023 day 9
+        let histories = [
+            vec![0, 3, 6, 9, 12, 15],
+            vec![1, 3, 6, 10, 15, 21],
+            vec![10, 13, 16, 21, 30, 45],
+        ];
+        assert_eq!(
+            histories
+                .iter()
+                .map(|h| next_value(h).unwrap())
+                .sum::<i64>(),
+            114
+        );
+        assert_eq!(
+            histories
+                .iter()
+                .map(|h| previous_value(h).unwrap())
+                .sum::<i64>(),
+            2
+        );
+        assert_eq!(
+            difference_table(&histories[2]).unwrap(),
+            [
+                vec![10, 13, 16, 21, 30, 45],
+                vec![3, 3, 5, 9, 15],
+                vec![0, 2, 4, 6],
+                vec![2, 2, 2],
+                vec![0, 0]
+            ]
+        );
+        for history in &histories {
+            assert_eq!(value_at(history, 6), next_value(history).map(i128::from));
+            assert_eq!(
+                value_at(history, -1),
+                previous_value(history).map(i128::from)
+            );
+        }
+        assert_eq!(value_at(&[7], 1_000_000), Some(7));
+        assert_eq!(value_at(&[], 5), Some(0));
+        assert_eq!((next_value(&[]), previous_value(&[])), (Some(0), Some(0)));
+
+        // Overflowing differences or results, never a panic
+        assert_eq!(difference_table(&[i64::MIN, i64::MAX]), None);
+        assert_eq!(value_at(&[i64::MIN, i64::MAX], 5), None);
+        assert_eq!(next_value(&[i64::MAX - 1, i64::MAX]), None);
+        assert_eq!(previous_value(&[i64::MIN, i64::MIN + 1]), None);
+        assert_eq!(next_value(&[i64::MAX, 0, i64::MAX]), None);
+        assert_eq!(
+            value_at(&[i64::MAX - 1, i64::MAX], 2),
+            Some(i64::MAX as i128 + 1)
+        );
+        assert_eq!(value_at(&[0, 1], i128::MAX), Some(i128::MAX));
+        assert_eq!(value_at(&[0, 1, 4], i128::MAX), None);
+    }
+
+    #[test]
+    fn fits_garden_growth() {
+        // 2023 day 21: on the tiled example map, plots reachable in 6 + 11k steps grow
+        // quadratically in k once k >= 3
+        let garden: DenseGrid2D<char> = "...........\n.....###.#.\n.###.##..#.\n..#.#...#..\n....#.#....\n.##..S####.\n.##..#...#.\n.......##..\n.##.#.####.\n.##..##.##.\n...........\n"
+            .parse()
+            .unwrap();
+        let tiled = garden.tiled().unwrap();
+        let (x, y) = garden.find(&'S').unwrap();
+        let mut frontier = HashSet::from([Point2::new(x as i64, y as i64)]);
+        let mut samples = Vec::new();
+        for steps in 1..=72 {
+            frontier = frontier
+                .iter()
+                .flat_map(|pos| tiled.neighbors4(*pos))
+                .filter(|pos| *tiled.get(*pos) != '#')
+                .collect();
+            if [50, 61, 72].contains(&steps) {
+                samples.push(frontier.len() as i64);
+            }
+        }
+        assert_eq!(samples, [1594, 2406, 3380]);
+
+        // 5000 steps is k = 454, 450 past the first sample
+        assert_eq!(value_at(&samples, 450), Some(16733044));
+        let points = [(50, 1594), (61, 2406), (72, 3380)];
+        assert_eq!(
+            lagrange(&points, 5000).and_then(|r| r.to_integer()),
+            Some(16733044)
+        );
+        let n = 1_000_000_000_000_000;
+        assert_eq!(
+            value_at(&samples, n),
+            Some(1594 + 812 * n + 162 * (n * (n - 1) / 2))
+        );
+    }
+
+    #[test]
+    fn interpolates_exactly() {
+        assert_eq!(lagrange(&[(0, 0), (2, 1)], 1), Some(Ratio::new(1, 2)));
+        assert_eq!(Ratio::new(4, -6).to_string(), "-2/3");
+        assert_eq!(Ratio::new(i128::MIN, 2).numer(), i128::MIN / 2);
+        assert_eq!(
+            Ratio::new(i128::MIN, 1).checked_mul(Ratio::new(1, -1)),
+            None
+        );
+        // y = x² / 4
+        assert_eq!(
+            lagrange(&[(0, 0), (2, 1), (4, 4)], 1).map(|r| r.to_string()),
+            Some("1/4".to_string())
+        );
+        assert_eq!(
+            lagrange(&[(1, 1), (2, 4), (-3, 9)], 3),
+            Some(Ratio::from(9))
+        );
+        assert_eq!(
+            Ratio::new(1, 3).checked_add(Ratio::new(1, 6)),
+            Some(Ratio::new(1, 2))
+        );
+        assert_eq!(lagrange(&[(0, 5)], 1 << 100), Some(Ratio::from(5)));
+    }
+}
@@ -0,0 +1,845 @@
+//! `nom` combinators for the shapes puzzle inputs keep coming in, and `all` to run one over
+//! a whole input with errors that say where it went wrong.  For lines that are just numbers
+//! among punctuation, `ints` and friends skip the grammar altogether.
+//!
+//! ```
+//! use aoc::parse::{adjacency, all, lines, word};
+//!
+//! let graph = all("a -> b, c\nb -> c\n", lines(adjacency(word))).unwrap();
+//! assert_eq!(graph[0], ("a", vec!["b", "c"]));
+//! ```
+
+use crate::DenseGrid2D;
+use anyhow::{anyhow, bail, Context, Result};
+use nom::bytes::complete::{tag, take_while1};
+use nom::character::complete::{char, digit1, line_ending, multispace0, one_of, space0, space1};
+use nom::combinator::{all_consuming, map_res, opt, recognize};
+use nom::error::ErrorKind;
+use nom::multi::{many1, separated_list1};
+use nom::sequence::{delimited, pair, separated_pair, terminated, tuple};
+use nom::Offset;
+use std::fmt;
+use std::str::FromStr;
+
+pub type IResult<'a, T> = nom::IResult<&'a str, T>;
+
+/// Where in the input parsing failed: 1-based line and column, the width of the bad text
+/// and the whole line it is on, so it can be shown with `snippet`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error about the `len` bytes at byte `offset` of `input`
+    pub fn at(input: &str, offset: usize, len: usize, message: impl Into<String>) -> Self {
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let text = input[line_start..].lines().next().unwrap_or_default();
+        let end = (offset + len).min(line_start + text.len()).max(offset);
+        ParseError {
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            len: input[offset..end].chars().count().max(1),
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// An error about the whole of `line`, which must be a slice of `input`
+    pub fn line(input: &str, line: &str, message: impl Into<String>) -> Self {
+        ParseError::at(input, input.offset(line), line.len(), message)
+    }
+
+    /// The line with the bad text underlined:
+    ///
+    /// ```text
+    ///   --> line 2, column 3
+    ///    |
+    ///  2 | 3 x 4
+    ///    |   ^ unexpected text
+    /// ```
+    pub fn snippet(&self) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        format!(
+            "{gutter}--> line {}, column {}\n{gutter} |\n{} | {}\n{gutter} | {}{} {}\n",
+            self.line,
+            self.column,
+            number,
+            self.text,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.len),
+            self.message,
+        )
+    }
+
+    /// A copy for an input this one's was part of, starting `lines` lines further down
+    fn shifted(&self, lines: usize) -> Self {
+        ParseError {
+            line: self.line + lines,
+            ..self.clone()
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Parse error at line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The first `ParseError` behind `err`, if there is one
+pub fn find_parse_error(err: &anyhow::Error) -> Option<&ParseError> {
+    err.chain().find_map(|e| e.downcast_ref::<ParseError>())
+}
+
+/// Print the snippet of the `ParseError` behind `err` to stderr, if there is one
+pub fn print_snippet(err: &anyhow::Error) {
+    if let Some(parse_error) = find_parse_error(err) {
+        eprintln!("{}", parse_error.snippet());
+    }
+}
+
+/// Parse every line of `text`, a slice of `input`, with its `FromStr`
+pub(crate) fn parse_lines<T>(input: &str, text: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    text.lines()
+        .map(|line| {
+            line.parse::<T>()
+                .map_err(|e| ParseError::line(input, line, e.to_string()).into())
+        })
+        .collect()
+}
+
+/// An integer with an optional sign, e.g. `-12` or `+3`
+pub fn int<T: FromStr>(input: &str) -> IResult<'_, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(input)
+}
+
+/// An integer without a sign
+pub fn uint<T: FromStr>(input: &str) -> IResult<'_, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// A run of letters, digits and underscores, e.g. a node or register name
+pub fn word(input: &str) -> IResult<'_, &str> {
+    take_while1(|c: char| c.is_alphanumeric() || c == '_')(input)
+}
+
+/// One or more `item`s separated by spaces or tabs
+pub fn spaced<'a, T, F>(item: F) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>>
+where
+    F: FnMut(&'a str) -> IResult<'a, T>,
+{
+    separated_list1(space1, item)
+}
+
+/// One or more `item`s separated by commas, with or without spaces after them
+pub fn comma_separated<'a, T, F>(item: F) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>>
+where
+    F: FnMut(&'a str) -> IResult<'a, T>,
+{
+    separated_list1(pair(char(','), space0), item)
+}
+
+/// `key: value`
+pub fn record<'a, K, V, FK, FV>(key: FK, value: FV) -> impl FnMut(&'a str) -> IResult<'a, (K, V)>
+where
+    FK: FnMut(&'a str) -> IResult<'a, K>,
+    FV: FnMut(&'a str) -> IResult<'a, V>,
+{
+    separated_pair(key, pair(char(':'), space0), value)
+}
+
+/// `a -> b, c`: a node and the nodes it leads to
+pub fn adjacency<'a, T, F>(mut node: F) -> impl FnMut(&'a str) -> IResult<'a, (T, Vec<T>)>
+where
+    F: FnMut(&'a str) -> IResult<'a, T>,
+{
+    move |input| {
+        let (input, from) = node(input)?;
+        let (input, _) = delimited(space0, tag("->"), space0)(input)?;
+        let (input, to) = comma_separated(&mut node)(input)?;
+        Ok((input, (from, to)))
+    }
+}
+
+/// One `item` per line
+pub fn lines<'a, T, F>(item: F) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>>
+where
+    F: FnMut(&'a str) -> IResult<'a, T>,
+{
+    separated_list1(line_ending, item)
+}
+
+/// Sections separated by one or more blank lines, each parsed by `section`
+pub fn sections<'a, T, F>(section: F) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>>
+where
+    F: FnMut(&'a str) -> IResult<'a, T>,
+{
+    separated_list1(pair(line_ending, many1(line_ending)), section)
+}
+
+/// Parse the whole of `input`, allowing only trailing whitespace after what `parser` reads
+pub fn all<'a, T, F>(input: &'a str, parser: F) -> Result<T>
+where
+    F: FnMut(&'a str) -> IResult<'a, T>,
+{
+    match all_consuming(terminated(parser, multispace0))(input) {
+        Ok((_, value)) => Ok(value),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+            Err(ParseError::at(input, input.offset(e.input), 1, expected(e.code)).into())
+        }
+        Err(nom::Err::Incomplete(_)) => Err(anyhow!("Parse error: input ended early")),
+    }
+}
+
+/// `item` with any spaces or tabs around it
+pub fn padded<'a, T, F>(item: F) -> impl FnMut(&'a str) -> IResult<'a, T>
+where
+    F: FnMut(&'a str) -> IResult<'a, T>,
+{
+    delimited(space0, item, space0)
+}
+
+/// Three `item`s separated by commas, e.g. a 3D point
+pub fn triple<'a, T, F>(mut item: F) -> impl FnMut(&'a str) -> IResult<'a, (T, T, T)>
+where
+    F: FnMut(&'a str) -> IResult<'a, T>,
+{
+    move |input| {
+        let comma = |i| tuple((space0, char(','), space0))(i);
+        let (input, a) = item(input)?;
+        let (input, _) = comma(input)?;
+        let (input, b) = item(input)?;
+        let (input, _) = comma(input)?;
+        let (input, c) = item(input)?;
+        Ok((input, (a, b, c)))
+    }
+}
+
+/// The digits of every number in `text`, with the sign if `signed`.  A sign only counts
+/// when it doesn't follow a letter or digit, so `1-3` is the range from 1 to 3.
+fn numbers(text: &str, signed: bool) -> impl Iterator<Item = &str> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        while i < bytes.len() && !bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i == bytes.len() {
+            return None;
+        }
+        let mut start = i;
+        if signed
+            && start > 0
+            && matches!(bytes[start - 1], b'-' | b'+')
+            && (start < 2 || !bytes[start - 2].is_ascii_alphanumeric())
+        {
+            start -= 1;
+        }
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        Some(&text[start..i])
+    })
+}
+
+/// Every integer in `text`, negative ones included, whatever separates them.  A number
+/// that doesn't fit in `T` is a `ParseError` pointing at it.
+pub fn ints<T>(text: &str) -> impl Iterator<Item = Result<T>> + '_
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    parse_numbers(text, true)
+}
+
+/// Every run of digits in `text`, ignoring any signs.  A number that doesn't fit in `T` is
+/// a `ParseError` pointing at it.
+pub fn uints<T>(text: &str) -> impl Iterator<Item = Result<T>> + '_
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    parse_numbers(text, false)
+}
+
+fn parse_numbers<T>(text: &str, signed: bool) -> impl Iterator<Item = Result<T>> + '_
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    numbers(text, signed).map(|n| {
+        n.parse().map_err(|e| {
+            let message = format!("invalid integer {}: {}", n, e);
+            ParseError::at(text, text.offset(n), n.len(), message).into()
+        })
+    })
+}
+
+/// Exactly `N` integers from `text`, e.g. `let [x, y, z] = ints_fixed(line)?`
+pub fn ints_fixed<T, const N: usize>(text: &str) -> Result<[T; N]>
+where
+    T: FromStr + fmt::Debug,
+    T::Err: fmt::Display,
+{
+    let values = ints(text).collect::<Result<Vec<_>>>()?;
+    let found = values.len();
+    values
+        .try_into()
+        .map_err(|_| anyhow!("Expected {} integers in {:?}, found {}", N, text, found))
+}
+
+/// A grid of single digits, e.g. heights or heat losses
+pub fn digit_grid(input: &str) -> Result<DenseGrid2D<u8>> {
+    let bad = input
+        .trim_end()
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii_digit() && *c != '\n' && *c != '\r');
+    if let Some((offset, c)) = bad {
+        let message = format!("invalid cell {:?}: not a digit", c);
+        return Err(ParseError::at(input, offset, c.len_utf8(), message).into());
+    }
+    let grid: DenseGrid2D<char> = input.parse()?;
+    Ok(grid.map(|c| *c as u8 - b'0'))
+}
+
+/// The blank-line separated sections of `input`, ignoring blank lines at either end and
+/// however many separate two sections.  Lines holding only whitespace count as blank, so
+/// `\r\n` endings work too.  Each section keeps its inner line breaks but not its last one.
+pub fn split_sections(input: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        if content.trim().is_empty() {
+            if let Some(start) = start.take() {
+                sections.push(&input[start..end]);
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + content.len();
+        }
+        offset += line.len();
+    }
+    if let Some(start) = start {
+        sections.push(&input[start..end]);
+    }
+    sections
+}
+
+/// Parses the sections of an input one after another, naming each so an error says which
+/// part of the input was wrong.
+///
+/// ```
+/// use aoc::parse::Sections;
+///
+/// let mut sections = Sections::new("seeds: 79 14\r\n\r\n\r\nsoil:\r\n50 98 2\r\n");
+/// let seeds: Vec<u64> = sections.next("seeds", |s| aoc::ints(s).collect())?;
+/// let maps = sections.rest("maps", |s| Ok(s.lines().count()))?;
+/// assert_eq!((seeds, maps), (vec![79, 14], vec![2]));
+/// # anyhow::Ok(())
+/// ```
+pub struct Sections<'a> {
+    input: &'a str,
+    sections: Vec<&'a str>,
+    next: usize,
+}
+
+impl<'a> Sections<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Sections {
+            input,
+            sections: split_sections(input),
+            next: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sections.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Parse the next section with `parser`
+    pub fn next<T, F>(&mut self, name: &str, parser: F) -> Result<T>
+    where
+        F: FnOnce(&'a str) -> Result<T>,
+    {
+        let index = self.next;
+        let section = self.sections.get(index).with_context(|| {
+            format!(
+                "Missing section {} ({}): the input only has {}",
+                index + 1,
+                name,
+                self.sections.len()
+            )
+        })?;
+        self.next += 1;
+        parser(section)
+            .map_err(|mut e| {
+                // Report lines of the whole input rather than of the section.  Sections
+                // start at the start of a line, so columns are already right.
+                let lines_before = self.input[..self.input.offset(section)]
+                    .matches('\n')
+                    .count();
+                if let Some(parse_error) = e.downcast_mut::<ParseError>() {
+                    *parse_error = parse_error.shifted(lines_before);
+                }
+                e
+            })
+            .with_context(|| format!("Failed to parse section {} ({})", index + 1, name))
+    }
+
+    /// Parse the next section with its type's `FromStr`, keeping a [`ParseError`] it
+    /// returns so it points into the whole input
+    pub fn parse<T>(&mut self, name: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Into<anyhow::Error>,
+    {
+        self.next(name, |section| section.parse().map_err(Into::into))
+    }
+
+    /// Parse every section left with `parser`
+    pub fn rest<T, F>(&mut self, name: &str, mut parser: F) -> Result<Vec<T>>
+    where
+        F: FnMut(&'a str) -> Result<T>,
+    {
+        let mut values = Vec::new();
+        while self.next < self.sections.len() {
+            values.push(self.next(name, &mut parser)?);
+        }
+        Ok(values)
+    }
+
+    /// Fail if any section hasn't been parsed
+    pub fn finish(self) -> Result<()> {
+        if self.next < self.sections.len() {
+            bail!(
+                "Unexpected section {} of {}: {:?}",
+                self.next + 1,
+                self.sections.len(),
+                self.sections[self.next].lines().next().unwrap_or_default()
+            );
+        }
+        Ok(())
+    }
+}
+
+fn expected(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Digit => "expected a number".to_string(),
+        ErrorKind::MapRes => "value out of range".to_string(),
+        ErrorKind::Eof => "unexpected text".to_string(),
+        ErrorKind::Char | ErrorKind::Tag | ErrorKind::OneOf => "unexpected character".to_string(),
+        ErrorKind::TakeWhile1 => "expected a name".to_string(),
+        ErrorKind::Space | ErrorKind::CrLf => "expected whitespace".to_string(),
+        kind => format!("failed to match {}", kind.description()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbers_and_lists() {
+        assert_eq!(all("-12", int::<i64>).unwrap(), -12);
+        assert_eq!(all("+3\n", int::<i8>).unwrap(), 3);
+        assert!(all("-3", uint::<u32>).is_err());
+        assert_eq!(all("0 3 -6  9", spaced(int::<i32>)).unwrap(), [0, 3, -6, 9]);
+        assert_eq!(
+            all("1,2, 3", comma_separated(uint::<u8>)).unwrap(),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            all(
+                "19, 13, 30 @ -2,  1, -2",
+                separated_pair(triple(int::<i64>), padded(char('@')), triple(int::<i64>))
+            )
+            .unwrap(),
+            ((19, 13, 30), (-2, 1, -2))
+        );
+    }
+
+    #[test]
+    fn parses_records_and_graphs() {
+        // 2023 day 2
+        let games = all(
+            "Game 1: 3 blue, 4 red\r\nGame 2: 1 green\r\n",
+            lines(record(
+                pair(tag("Game "), uint::<u32>),
+                comma_separated(separated_pair(uint::<u32>, char(' '), word)),
+            )),
+        )
+        .unwrap();
+        assert_eq!(games[1], (("Game ", 2), vec![(1, "green")]));
+
+        // 2023 day 20
+        let modules = all(
+            "broadcaster -> a, b, c\n%a -> b\n&inv -> a\n",
+            lines(pair(opt(one_of("%&")), adjacency(word))),
+        )
+        .unwrap();
+        assert_eq!(modules[0], (None, ("broadcaster", vec!["a", "b", "c"])));
+        assert_eq!(modules[2], (Some('&'), ("inv", vec!["a"])));
+    }
+
+    #[test]
+    fn parses_sections() {
+        let groups = all(
+            "1000\n2000\n\n4000\n\n\n5000\n6000\n",
+            sections(lines(uint::<u32>)),
+        )
+        .unwrap();
+        assert_eq!(groups, [vec![1000, 2000], vec![4000], vec![5000, 6000]]);
+    }
+
+    #[test]
+    fn reports_line_and_column() {
+        let err = all("1 2\n3 x 4\n", lines(spaced(uint::<u32>))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parse error at line 2, column 3: unexpected text"
+        );
+        assert_eq!(
+            err.downcast_ref::<ParseError>().unwrap().snippet(),
+            " --> line 2, column 3\n  |\n2 | 3 x 4\n  |   ^ unexpected text\n"
+        );
+        let err = all("300", uint::<u8>).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parse error at line 1, column 1: value out of range"
+        );
+    }
+
+    #[test]
+    fn extracts_integers() {
+        // 2023 days 5, 22 and 24
+        assert_eq!(
+            ints::<u64>("seeds: 79 14 55 13")
+                .collect::<Result<Vec<_>>>()
+                .unwrap(),
+            [79, 14, 55, 13]
+        );
+        let [x1, y1, z1, x2, y2, z2] = ints_fixed::<u32, 6>("1,0,1~1,2,1").unwrap();
+        assert_eq!((x1, y1, z1, x2, y2, z2), (1, 0, 1, 1, 2, 1));
+        assert_eq!(
+            ints::<i64>("19, 13, 30 @ -2,  1, -2")
+                .collect::<Result<Vec<_>>>()
+                .unwrap(),
+            [19, 13, 30, -2, 1, -2]
+        );
+
+        assert_eq!(
+            ints::<i32>("x=-5..7, +2")
+                .collect::<Result<Vec<_>>>()
+                .unwrap(),
+            [-5, 7, 2]
+        );
+        assert_eq!(
+            ints::<i32>("1-3 a, b-2")
+                .collect::<Result<Vec<_>>>()
+                .unwrap(),
+            [1, 3, 2]
+        );
+        assert_eq!(
+            uints::<u8>("x=-5..-7").collect::<Result<Vec<_>>>().unwrap(),
+            [5, 7]
+        );
+        assert_eq!(ints::<i32>("no numbers").count(), 0);
+
+        let err = ints_fixed::<i32, 3>("1,2").unwrap_err();
+        assert_eq!(err.to_string(), "Expected 3 integers in \"1,2\", found 2");
+        assert!(ints_fixed::<u8, 1>("-1").is_err());
+    }
+
+    #[test]
+    fn reports_integers_that_do_not_fit() {
+        let err = ints::<u8>("1 300").collect::<Result<Vec<_>>>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parse error at line 1, column 3: invalid integer 300: number too large to fit in target type"
+        );
+        let err = ints::<u32>("x=-5").collect::<Result<Vec<_>>>().unwrap_err();
+        let error = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((error.column, error.len), (3, 2));
+        let mut too_long = uints::<u64>("99999999999999999999");
+        assert!(too_long.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn parses_digit_grids() {
+        let grid = digit_grid("2413\n3215\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (4, 2));
+        assert_eq!(grid[(3, 1)], 5);
+        let err = digit_grid("24\n3.\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parse error at line 2, column 2: invalid cell '.': not a digit"
+        );
+    }
+
+    #[test]
+    fn splits_sections() {
+        assert_eq!(split_sections("\n\na\nb\n\n\n\nc\n\n"), ["a\nb", "c"]);
+        assert_eq!(split_sections("a\r\nb\r\n \r\nc\r\n"), ["a\r\nb", "c"]);
+        assert_eq!(split_sections("a"), ["a"]);
+        assert!(split_sections("\n \n").is_empty());
+    }
+
+    #[test]
+    fn parses_named_sections() {
+        // 2023 day 19
+        let input = "px{a<2006:qkq,m>2090:A,rfg}\npv{a>1716:R,A}\n\n{x=787,m=2655,a=1222,s=2876}\n";
+        let mut sections = Sections::new(input);
+        let workflows = sections
+            .next("workflows", |s| Ok(s.lines().count()))
+            .unwrap();
+        let ratings = sections
+            .next("ratings", |s| {
+                s.lines()
+                    .map(ints_fixed::<u32, 4>)
+                    .collect::<Result<Vec<_>>>()
+            })
+            .unwrap();
+        assert_eq!((workflows, ratings), (2, vec![[787, 2655, 1222, 2876]]));
+        assert!(sections.finish().is_ok());
+
+        let mut sections = Sections::new("1\n\nx\n");
+        assert_eq!(sections.parse::<u8>("count").unwrap(), 1);
+        let err = sections.parse::<u8>("size").unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "Failed to parse section 2 (size): invalid digit found in string"
+        );
+        let err = sections.parse::<u8>("extra").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Missing section 3 (extra): the input only has 2"
+        );
+
+        // Positions are in the whole input, not the section
+        let mut sections = Sections::new("1\n\n12\n3x\n");
+        sections.parse::<u8>("count").unwrap();
+        let err = sections.next("map", digit_grid).unwrap_err();
+        let parse_error = find_parse_error(&err).unwrap();
+        assert_eq!((parse_error.line, parse_error.column), (4, 2));
+        assert_eq!(parse_error.text, "3x");
+
+        let mut sections = Sections::new("1\n\n\n12\n3€\n");
+        sections.parse::<u8>("count").unwrap();
+        let err = sections.parse::<DenseGrid2D<u8>>("map").unwrap_err();
+        let parse_error = find_parse_error(&err).unwrap();
+        assert_eq!((parse_error.line, parse_error.column), (5, 2));
+        assert_eq!(
+            format!("{:#}", err),
+            "Failed to parse section 2 (map): Parse error at line 5, column 2: invalid cell '€': unicode code point out of range"
+        );
+
+        let mut sections = Sections::new("a\n\nb\n\nc");
+        sections.next("first", |_| Ok(())).unwrap();
+        assert_eq!(
+            sections.finish().unwrap_err().to_string(),
+            "Unexpected section 2 of 3: \"b\""
+        );
+    }
+
+    mod derived {
+        use crate::geom::{Dir4, Point3};
+        use crate::AocParse;
+
+        // 2023 day 22
+        #[derive(Debug, PartialEq, AocParse)]
+        #[aoc(fmt = "{x},{y},{z}~{x2},{y2},{z2}")]
+        struct Brick {
+            x: u32,
+            y: u32,
+            z: u32,
+            x2: u32,
+            y2: u32,
+            z2: u32,
+        }
+
+        // 2023 day 24
+        #[derive(Debug, PartialEq, AocParse)]
+        #[aoc(fmt = "{0} @ {1}")]
+        struct HailStone(Point3<i64>, Point3<i64>);
+
+        // 2023 day 18
+        #[derive(Debug, PartialEq, AocParse)]
+        #[aoc(fmt = "{dir} {steps} (#{color})")]
+        struct Instruction {
+            dir: Dir4,
+            steps: u32,
+            color: String,
+        }
+
+        // 2023 day 19
+        #[derive(Debug, PartialEq, AocParse)]
+        #[aoc(fmt = "{{x={x},m={m},a={a},s={s}}}")]
+        struct Part {
+            x: u32,
+            m: u32,
+            a: u32,
+            s: u32,
+        }
+
+        // 2023 day 20
+        #[derive(Debug, PartialEq, AocParse)]
+        enum Module {
+            #[aoc(fmt = "%{0} -> {1:,}")]
+            FlipFlop(String, Vec<String>),
+            #[aoc(fmt = "&{name} -> {outputs:,}")]
+            Conjunction { name: String, outputs: Vec<String> },
+            #[aoc(fmt = "broadcaster -> {outputs:,}")]
+            Broadcaster { outputs: Vec<String> },
+            #[aoc(fmt = "button")]
+            Button,
+        }
+
+        // 2015 day 6, plus a catch-all sharing the "turn " prefix
+        #[derive(Debug, PartialEq, AocParse)]
+        enum Light {
+            #[aoc(fmt = "turn on {0},{1}")]
+            On(u32, u32),
+            #[aoc(fmt = "turn off {0},{1}")]
+            Off(u32, u32),
+            #[aoc(fmt = "toggle {0},{1}")]
+            Toggle(u32, u32),
+            #[aoc(fmt = "turn {0} {1}")]
+            Turn(String, u32),
+        }
+
+        #[derive(Debug, PartialEq, AocParse)]
+        #[aoc(fmt = "Card {id}: {winning} | {have}")]
+        struct Card {
+            id: u32,
+            winning: Vec<u32>,
+            have: Vec<u32>,
+        }
+
+        #[test]
+        fn derives_from_str() {
+            let brick: Brick = "1,0,1~1,2,1".parse().unwrap();
+            assert_eq!((brick.x, brick.y2, brick.z2), (1, 2, 1));
+            let hail: HailStone = "19, 13, 30 @ -2,  1, -2\r\n".parse().unwrap();
+            assert_eq!(hail.1, Point3::new(-2, 1, -2));
+            let instruction: Instruction = "R 6 (#70c710)".parse().unwrap();
+            assert_eq!(instruction.dir, Dir4::East);
+            assert_eq!(instruction.color, "70c710");
+            let part: Part = "{x=787,m=2655,a=1222,s=2876}".parse().unwrap();
+            assert_eq!(part.s, 2876);
+            let card: Card = "Card 1: 41 48 83 | 83  86 6".parse().unwrap();
+            assert_eq!(
+                (card.winning, card.have),
+                (vec![41, 48, 83], vec![83, 86, 6])
+            );
+        }
+
+        #[derive(Debug, PartialEq, AocParse)]
+        #[aoc(fmt = "{name}: {count}")]
+        struct Tally {
+            name: String,
+            count: u32,
+        }
+
+        #[test]
+        fn tries_later_occurrences_of_a_literal() {
+            let tally: Tally = "note: see below: 3".parse().unwrap();
+            assert_eq!(tally.name, "note: see below");
+            assert_eq!(tally.count, 3);
+            // No split works, so the leftmost one explains why
+            let err = "note: see below: x".parse::<Tally>().unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Failed to parse Tally from \"note: see below: x\": `count` from \"see below: x\": invalid digit found in string"
+            );
+        }
+
+        #[test]
+        fn derives_enum_variants_by_prefix() {
+            let modules = "broadcaster -> a, b\n%a -> inv\n&inv -> a\nbutton\n"
+                .lines()
+                .map(str::parse)
+                .collect::<anyhow::Result<Vec<Module>>>()
+                .unwrap();
+            assert_eq!(
+                modules,
+                [
+                    Module::Broadcaster {
+                        outputs: vec!["a".to_string(), "b".to_string()]
+                    },
+                    Module::FlipFlop("a".to_string(), vec!["inv".to_string()]),
+                    Module::Conjunction {
+                        name: "inv".to_string(),
+                        outputs: vec!["a".to_string()]
+                    },
+                    Module::Button,
+                ]
+            );
+        }
+
+        #[test]
+        fn falls_through_shared_prefixes() {
+            let lights = "turn on 0,0\nturn off 1,1\ntoggle 2,2\nturn left 90\nturn on 7"
+                .lines()
+                .map(str::parse)
+                .collect::<anyhow::Result<Vec<Light>>>()
+                .unwrap();
+            assert_eq!(
+                lights,
+                [
+                    Light::On(0, 0),
+                    Light::Off(1, 1),
+                    Light::Toggle(2, 2),
+                    Light::Turn("left".to_string(), 90),
+                    // "turn on" matched but needs a comma
+                    Light::Turn("on".to_string(), 7),
+                ]
+            );
+            // Every variant that matched failed; the most specific one explains why
+            let err = "turn on x".parse::<Light>().unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Failed to parse Light from \"turn on x\": expected \",\" after \"x\""
+            );
+        }
+
+        #[test]
+        fn explains_failures() {
+            let err = "1,0,1~1,x,1".parse::<Brick>().unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Failed to parse Brick from \"1,0,1~1,x,1\": `y2` from \"x\": invalid digit found in string"
+            );
+            let err = "1,0,1".parse::<Brick>().unwrap_err();
+            assert!(err.to_string().ends_with("expected \"~\" after \"1\""));
+            let err = "R 6 (#70c710) extra".parse::<Instruction>().unwrap_err();
+            assert!(err
+                .to_string()
+                .ends_with("unexpected \" extra\" at the end"));
+            let err = "?x -> y".parse::<Module>().unwrap_err();
+            assert!(err.to_string().contains("one of"));
+        }
+    }
+}
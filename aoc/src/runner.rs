@@ -0,0 +1,436 @@
+//! Registry entries generated by `#[aoc::main]` and the in-process runner behind `aoc run`
+
+use crate::{submit, Cli, Config, Ledger, Puzzle, Timing};
+use anyhow::{anyhow, bail, Result};
+
+/// Answer and timing for one part of a solution
+#[derive(Debug, Clone)]
+pub struct PartRun {
+    pub part: u8,
+    pub answer: String,
+    pub time: Timing,
+}
+
+/// Everything produced by running a solution against one input
+#[derive(Debug, Clone)]
+pub struct SolutionRun {
+    pub parse_time: Option<Timing>,
+    pub parts: Vec<PartRun>,
+}
+
+/// A solution binary, exported by `#[aoc::main]` as `SOLUTION` and collected into each
+/// year crate's `SOLUTIONS` so it can be run in-process
+#[derive(Clone, Copy)]
+pub struct Solution {
+    /// Source file of the solution, as given by `file!()`
+    pub file: &'static str,
+    /// Puzzle solved, from the attribute's `year`/`day`/`part` or else the file path
+    pub puzzle: Puzzle,
+    /// Parts the solution answers, so the runner can skip it without loading its input
+    pub parts: &'static [u8],
+    /// Runs the solution on an input, optionally restricted to one part, repeating each
+    /// phase the given number of times
+    pub run: fn(&str, Option<u8>, usize) -> Result<SolutionRun>,
+}
+
+impl Solution {
+    /// File stem, e.g. `day7_part2`, to tell apart several solutions of the same puzzle
+    pub fn name(&self) -> &'static str {
+        let file = self.file.rsplit('/').next().unwrap_or(self.file);
+        file.strip_suffix(".rs").unwrap_or(file)
+    }
+}
+
+/// Print the answers of a solution binary and submit one of them if `--submit` was given
+pub fn finish_script(puzzle: &Puzzle, args: &Cli, run: &SolutionRun) -> Result<()> {
+    if let Some(parse_time) = &run.parse_time {
+        println!("Parse time: {}", parse_time);
+    }
+    // Answers from someone else's input say nothing about our ledger
+    if args.input.is_some() && (args.check || args.submit) {
+        bail!("--check and --submit only apply to your own cached input, not --input");
+    }
+    let config = Config::from_args(&args.config)?;
+    let ledger = match args.input {
+        Some(_) => None,
+        None => Some(Ledger::for_puzzle(&config, puzzle)?),
+    };
+
+    let mut regressions = 0;
+    for part in &run.parts {
+        let label = if part.part == 1 {
+            "Part one"
+        } else {
+            "Part two"
+        };
+        let note = match ledger
+            .as_ref()
+            .map(|l| (l, l.check(part.part, &part.answer)))
+        {
+            Some((ledger, Ok(()))) if ledger.correct(part.part).is_some() => {
+                " (accepted)".to_string()
+            }
+            Some((_, Err(e))) => {
+                regressions += 1;
+                format!(" ({})", e)
+            }
+            _ => String::new(),
+        };
+        println!("{}: {}{}", label, part.answer, note);
+        println!("Time: {}", part.time);
+    }
+    if args.check {
+        if regressions > 0 {
+            bail!("{} answer(s) contradict the ledger", regressions);
+        }
+        println!("All answers are consistent with the ledger");
+    }
+
+    // Submit the requested part, otherwise the first one still to be solved
+    let selected = match args.part {
+        Some(part) => run.parts.iter().find(|p| p.part == part),
+        None => first_unaccepted(run, ledger.as_ref()),
+    };
+    match selected {
+        Some(part) if args.submit => {
+            println!("Auto-submitting part {}", part.part);
+            let puzzle = Puzzle {
+                part: part.part,
+                ..*puzzle
+            };
+            submit(&config, &puzzle, &part.answer)?;
+        }
+        Some(_) => println!("To submit, rerun with --submit"),
+        None if args.part.is_none() && !run.parts.is_empty() => {
+            println!("Every part already has an accepted answer")
+        }
+        None => bail!("No solution for the requested part"),
+    }
+    Ok(())
+}
+
+/// The first part of `run` without an accepted answer in `ledger`
+fn first_unaccepted<'a>(run: &'a SolutionRun, ledger: Option<&Ledger>) -> Option<&'a PartRun> {
+    run.parts
+        .iter()
+        .find(|part| ledger.is_none_or(|ledger| ledger.correct(part.part).is_none()))
+}
+
+/// One line of the `aoc run` table
+pub struct Row {
+    pub day: usize,
+    pub part: u8,
+    pub name: &'static str,
+    pub outcome: Result<(String, Timing)>,
+}
+
+/// Run every solution for `year` (optionally only `day`/`part`), loading inputs with `load`.
+/// Failures are reported in the returned rows rather than stopping the run.
+pub fn run_solutions<F>(
+    solutions: &[Solution],
+    year: usize,
+    day: Option<usize>,
+    part: Option<u8>,
+    repeat: usize,
+    mut load: F,
+) -> Vec<Row>
+where
+    F: FnMut(&Puzzle) -> Result<String>,
+{
+    let mut selected = solutions
+        .iter()
+        .map(|solution| (solution.puzzle, solution))
+        .filter(|(puzzle, _)| puzzle.year == year && day.is_none_or(|d| d == puzzle.day))
+        .collect::<Vec<_>>();
+    selected.sort_by(|(a, x), (b, y)| (a.day, a.part, x.name()).cmp(&(b.day, b.part, y.name())));
+
+    let mut rows = Vec::new();
+    for (puzzle, solution) in selected {
+        let parts = solution
+            .parts
+            .iter()
+            .copied()
+            .filter(|p| part.is_none_or(|part| part == *p))
+            .collect::<Vec<_>>();
+        if parts.is_empty() {
+            continue;
+        }
+        let row = |part, outcome| Row {
+            day: puzzle.day,
+            part,
+            name: solution.name(),
+            outcome,
+        };
+        let input = match load(&puzzle) {
+            Ok(input) => input,
+            Err(e) => {
+                // Every part needs the input, so they all fail with it
+                let message = format!("{:#}", e);
+                rows.extend(parts.iter().map(|&p| row(p, Err(anyhow!(message.clone())))));
+                continue;
+            }
+        };
+        // One part at a time, so that an error is reported against the part that failed
+        for p in parts {
+            match (solution.run)(&input, Some(p), repeat) {
+                Ok(run) => rows.extend(
+                    run.parts
+                        .into_iter()
+                        .map(|run| row(run.part, Ok((run.answer, run.time)))),
+                ),
+                Err(e) => rows.push(row(p, Err(e))),
+            }
+        }
+    }
+    rows
+}
+
+pub fn format_table(rows: &[Row]) -> String {
+    let cells = rows
+        .iter()
+        .map(|row| {
+            let (answer, time) = match &row.outcome {
+                Ok((answer, time)) => (answer.clone(), time.to_string()),
+                Err(e) => (format!("error: {:#}", e), String::new()),
+            };
+            vec![
+                row.day.to_string(),
+                row.part.to_string(),
+                row.name.to_string(),
+                answer,
+                time,
+            ]
+        })
+        .collect::<Vec<_>>();
+    render_table(&["Day", "Part", "Solution", "Answer", "Time"], &cells)
+}
+
+/// Run a solution binary on the cached input of every profile and print a table of the
+/// answers, marking those that contradict the profile's ledger
+pub fn run_profiles(solution: &Solution, args: &Cli) -> Result<()> {
+    let puzzle = solution.puzzle;
+    let config = Config::from_args(&args.config)?;
+
+    let mut cells = Vec::new();
+    let mut failures = 0;
+    for profile in config.all_profiles() {
+        let name = profile.profile_name().to_string();
+        // Only cached inputs: a profile without one has nothing to compare
+        let input = std::fs::read_to_string(profile.input_path(&puzzle));
+        let run = match input {
+            Ok(input) => (solution.run)(&input, args.part, args.repeat),
+            Err(_) => Err(anyhow::anyhow!("no cached input")),
+        };
+        let run = match run {
+            Ok(run) => run,
+            Err(e) => {
+                failures += 1;
+                let part = args.part.unwrap_or(puzzle.part).to_string();
+                let error = format!("error: {:#}", e);
+                cells.push(vec![name, part, error, String::new(), String::new()]);
+                continue;
+            }
+        };
+
+        let ledger = Ledger::for_puzzle(&profile, &puzzle)?;
+        for part in run.parts {
+            let status = match ledger.check(part.part, &part.answer) {
+                Ok(()) if ledger.correct(part.part).is_some() => "accepted".to_string(),
+                Ok(()) => String::new(),
+                Err(e) => {
+                    failures += 1;
+                    e.to_string()
+                }
+            };
+            cells.push(vec![
+                name.clone(),
+                part.part.to_string(),
+                part.answer,
+                status,
+                part.time.to_string(),
+            ]);
+        }
+    }
+
+    print!(
+        "{}",
+        render_table(&["Profile", "Part", "Answer", "Ledger", "Time"], &cells)
+    );
+    if args.check && failures > 0 {
+        bail!(
+            "{} profile run(s) failed or contradict the ledger",
+            failures
+        );
+    }
+    Ok(())
+}
+
+/// Left-aligned columns separated by two spaces
+fn render_table(header: &[&str], cells: &[Vec<String>]) -> String {
+    let header = header.iter().map(|h| h.to_string()).collect::<Vec<_>>();
+    let mut widths = header.iter().map(|h| h.chars().count()).collect::<Vec<_>>();
+    for line in cells {
+        for (width, cell) in widths.iter_mut().zip(line) {
+            *width = std::cmp::max(*width, cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    for line in std::iter::once(&header).chain(cells) {
+        let padded = line
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>();
+        out.push_str(padded.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_parts(input: &str, part: Option<u8>, _repeat: usize) -> Result<SolutionRun> {
+        let mut parts = Vec::new();
+        for p in [1, 2] {
+            if part.is_none_or(|part| part == p) {
+                parts.push(PartRun {
+                    part: p,
+                    answer: format!("{}{}", input.trim(), p),
+                    time: Timing::new(vec![]),
+                });
+            }
+        }
+        Ok(SolutionRun {
+            parse_time: None,
+            parts,
+        })
+    }
+
+    fn failing(_input: &str, _part: Option<u8>, _repeat: usize) -> Result<SolutionRun> {
+        bail!("No solution found")
+    }
+
+    fn failing_part2(input: &str, part: Option<u8>, repeat: usize) -> Result<SolutionRun> {
+        if part.is_none_or(|part| part == 2) {
+            bail!("No solution found for part two");
+        }
+        two_parts(input, part, repeat)
+    }
+
+    const SOLUTIONS: &[Solution] = &[
+        Solution {
+            file: "solutions/2023/src/bin/day9.rs",
+            puzzle: Puzzle {
+                year: 2023,
+                day: 9,
+                part: 1,
+            },
+            parts: &[1, 2],
+            run: two_parts,
+        },
+        Solution {
+            file: "solutions/2023/src/bin/day7_part2.rs",
+            puzzle: Puzzle {
+                year: 2023,
+                day: 7,
+                part: 2,
+            },
+            parts: &[2],
+            run: failing,
+        },
+        Solution {
+            file: "solutions/2022/src/bin/day1.rs",
+            puzzle: Puzzle {
+                year: 2022,
+                day: 1,
+                part: 1,
+            },
+            parts: &[1, 2],
+            run: two_parts,
+        },
+    ];
+
+    #[test]
+    fn runs_matching_solutions_in_order() {
+        let rows = run_solutions(SOLUTIONS, 2023, None, None, 1, |puzzle| {
+            Ok(format!("d{}-", puzzle.day))
+        });
+        let summary = rows
+            .iter()
+            .map(|row| match &row.outcome {
+                Ok((answer, _)) => format!("{} {} {}", row.day, row.part, answer),
+                Err(e) => format!("{} {} {}", row.day, row.part, e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(summary, ["7 2 No solution found", "9 1 d9-1", "9 2 d9-2"]);
+    }
+
+    #[test]
+    fn filters_by_day_and_part() {
+        let rows = run_solutions(SOLUTIONS, 2023, Some(9), Some(2), 1, |_| Ok("x".into()));
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].outcome.as_ref().unwrap().0, "x2");
+
+        let table = format_table(&rows);
+        assert_eq!(
+            table.lines().next().unwrap(),
+            "Day  Part  Solution  Answer  Time"
+        );
+        assert_eq!(
+            table.lines().nth(1).unwrap(),
+            "9    2     day9      x2      0ns"
+        );
+    }
+
+    #[test]
+    fn only_loads_inputs_of_selected_parts() {
+        let mut loaded = Vec::new();
+        let rows = run_solutions(SOLUTIONS, 2023, None, Some(1), 1, |puzzle| {
+            loaded.push(puzzle.day);
+            Ok("x".into())
+        });
+        assert_eq!(loaded, [9]);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].outcome.as_ref().unwrap().0, "x1");
+    }
+
+    #[test]
+    fn reports_errors_against_the_failing_part() {
+        let solutions = [Solution {
+            file: "solutions/2023/src/bin/day3.rs",
+            puzzle: Puzzle {
+                year: 2023,
+                day: 3,
+                part: 1,
+            },
+            parts: &[1, 2],
+            run: failing_part2,
+        }];
+        let rows = run_solutions(&solutions, 2023, None, None, 1, |_| Ok("x".into()));
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].part, rows[0].outcome.is_ok()), (1, true));
+        assert_eq!((rows[1].part, rows[1].outcome.is_ok()), (2, false));
+
+        let rows = run_solutions(&solutions, 2023, None, None, 1, |_| bail!("no input"));
+        let parts = rows.iter().map(|row| row.part).collect::<Vec<_>>();
+        assert_eq!(parts, [1, 2]);
+        assert!(rows.iter().all(|row| row.outcome.is_err()));
+    }
+
+    #[test]
+    fn submits_the_first_part_without_an_accepted_answer() {
+        let run = two_parts("x", None, 1).unwrap();
+        assert_eq!(first_unaccepted(&run, None).unwrap().part, 1);
+
+        let path = std::env::temp_dir().join(format!("aoc-runner-{}.answers", std::process::id()));
+        let mut ledger = Ledger::load(&path).unwrap();
+        ledger.record(1, crate::ledger::Status::Correct, "x1").unwrap();
+        assert_eq!(first_unaccepted(&run, Some(&ledger)).unwrap().part, 2);
+        ledger.record(2, crate::ledger::Status::Correct, "x2").unwrap();
+        assert!(first_unaccepted(&run, Some(&ledger)).is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,231 @@
+//! `aoc new`: year crates and day files for a new puzzle
+
+use crate::{description, get_input_for_puzzle, Config, Puzzle};
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Used when `aoc.toml` doesn't name a `template`.  `{{year}}`, `{{day}}`, `{{example}}`
+/// (a string literal holding the first example of the puzzle) and `{{example_arg}}` (an
+/// `example(input = ...)` argument for `#[aoc::main]` after a comma, or nothing when there
+/// is no example) are filled in.
+pub const DEFAULT_TEMPLATE: &str = r#"use anyhow::{bail, Result};
+
+// Examples are checked once they have answers: `example(input = ..., part1 = ...)`
+#[aoc::main(part1{{example_arg}})]
+fn parse(input: &str) -> Result<Vec<String>> {
+    Ok(input.lines().map(String::from).collect())
+}
+
+fn part1(lines: &[String]) -> Result<usize> {
+    bail!("No solution found for {} lines", lines.len())
+}
+"#;
+
+/// Create the day file for `puzzle` in the workspace at `root`, creating and registering
+/// the year crate first if needed, and download the puzzle and input where possible
+pub fn new_day(root: &Path, config: &Config, puzzle: &Puzzle) -> Result<PathBuf> {
+    let crate_dir = root.join("solutions").join(puzzle.year.to_string());
+    if !crate_dir.exists() {
+        create_year_crate(root, puzzle.year)?;
+        println!("Created {}", crate_dir.display());
+    }
+
+    let path = crate_dir
+        .join("src")
+        .join("bin")
+        .join(format!("day{}.rs", puzzle.day));
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+
+    let template = match &config.template {
+        Some(template) => std::fs::read_to_string(template)
+            .with_context(|| format!("Failed to read template {}", template.display()))?,
+        None => DEFAULT_TEMPLATE.to_string(),
+    };
+    // Neither is needed to start, e.g. before the puzzle unlocks
+    let example = match description::puzzle_page(config, puzzle, false) {
+        Ok(html) => description::examples(&html).into_iter().next(),
+        Err(e) => {
+            println!("No example: {:#}", e);
+            None
+        }
+    };
+    if let Err(e) = get_input_for_puzzle(config, puzzle) {
+        println!("No input: {:#}", e);
+    }
+
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(&path, render(&template, puzzle, example.as_deref()))?;
+    Ok(path)
+}
+
+fn render(template: &str, puzzle: &Puzzle, example: Option<&str>) -> String {
+    let example_arg = example.map_or(String::new(), |example| {
+        format!(", example(input = {})", raw_string(example))
+    });
+    template
+        .replace("{{year}}", &puzzle.year.to_string())
+        .replace("{{day}}", &puzzle.day.to_string())
+        .replace("{{example}}", &raw_string(example.unwrap_or_default()))
+        .replace("{{example_arg}}", &example_arg)
+}
+
+/// A raw string literal with enough `#`s to hold `text`
+fn raw_string(text: &str) -> String {
+    let hashes = (0..)
+        .map(|n| "#".repeat(n))
+        .find(|hashes| !text.contains(&format!("\"{}", hashes)))
+        .unwrap();
+    format!("r{}\"{}\"{}", hashes, text, hashes)
+}
+
+fn create_year_crate(root: &Path, year: usize) -> Result<()> {
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = std::fs::read_to_string(&manifest_path)
+        .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+    let member = format!("solutions/{}", year);
+    let manifest = add_workspace_member(&manifest, &member)
+        .with_context(|| format!("No `members` list in {}", manifest_path.display()))?;
+
+    let crate_dir = root.join(&member);
+    std::fs::create_dir_all(crate_dir.join("src").join("bin"))?;
+    std::fs::write(
+        crate_dir.join("Cargo.toml"),
+        format!(
+            r#"[package]
+name = "aoc{}"
+version = "0.1.0"
+edition = "2021"
+rust-version = "1.88"
+
+# See more keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html
+
+[dependencies]
+anyhow = "1.0.75"
+aoc = {{ path = "../../aoc" }}
+
+[build-dependencies]
+aoc-build = {{ path = "../../aoc-build" }}
+"#,
+            year
+        ),
+    )?;
+    std::fs::write(
+        crate_dir.join("build.rs"),
+        "fn main() {\n    aoc_build::generate_solutions();\n}\n",
+    )?;
+    std::fs::write(
+        crate_dir.join("src").join("lib.rs"),
+        r#"//! Every solution binary of this year, compiled as a module so the workspace runner can
+//! call them in-process.  The module list is generated by `build.rs`.
+
+include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
+"#,
+    )?;
+    std::fs::write(&manifest_path, manifest)?;
+    Ok(())
+}
+
+/// The workspace manifest with `member` added to the top of the `members` list, where the
+/// newest year goes, or `None` if it has no such list
+fn add_workspace_member(manifest: &str, member: &str) -> Option<String> {
+    let quoted = format!("\"{}\"", member);
+    if manifest.contains(&quoted) {
+        return Some(manifest.to_string());
+    }
+    let start = manifest.find("members")?;
+    let open = start + manifest[start..].find('[')?;
+    let first_year = manifest[open..]
+        .find("\"solutions/")
+        .map(|i| open + i)
+        .filter(|i| *i < open + manifest[open..].find(']').unwrap_or(0));
+
+    let mut manifest = manifest.to_string();
+    match first_year {
+        Some(i) => {
+            let line_start = manifest[..i].rfind('\n').map_or(0, |n| n + 1);
+            let indent = manifest[line_start..i].to_string();
+            manifest.insert_str(line_start, &format!("{}{},\n", indent, quoted));
+        }
+        None => {
+            let close = open + manifest[open..].find(']')?;
+            manifest.insert_str(close, &format!("    {},\n", quoted));
+        }
+    }
+    Some(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_template() {
+        let puzzle = Puzzle {
+            year: 2016,
+            day: 7,
+            part: 1,
+        };
+        let day = render(DEFAULT_TEMPLATE, &puzzle, Some("abba[mnop]qrst\n"));
+        assert!(day.contains("#[aoc::main(part1, example(input = r\"abba[mnop]qrst\n\"))]"));
+        // Without an example there is nothing for it to check
+        let day = render(DEFAULT_TEMPLATE, &puzzle, None);
+        assert!(day.contains("#[aoc::main(part1)]"));
+        assert_eq!(
+            render("{{year}}/{{day}}: {{example}}", &puzzle, None),
+            "2016/7: r\"\""
+        );
+        assert_eq!(raw_string("say \"hi\""), "r#\"say \"hi\"\"#");
+    }
+
+    #[test]
+    fn registers_new_year_first() {
+        let manifest = "[workspace]\nmembers = [\n    \"aoc\",\n    \"solutions/2023\",\n    \"solutions/2022\",\n]\n";
+        let updated = add_workspace_member(manifest, "solutions/2024").unwrap();
+        assert_eq!(
+            updated,
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"solutions/2024\",\n    \"solutions/2023\",\n    \"solutions/2022\",\n]\n"
+        );
+        assert_eq!(
+            add_workspace_member(&updated, "solutions/2024").unwrap(),
+            updated
+        );
+        assert_eq!(
+            add_workspace_member(
+                "[workspace]\nmembers = [\n    \"aoc\",\n]\n",
+                "solutions/2015"
+            )
+            .unwrap(),
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"solutions/2015\",\n]\n"
+        );
+    }
+
+    #[test]
+    fn scaffolds_year_and_day() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\n]\n").unwrap();
+        let config = Config {
+            cache_dir: root.join("cache"),
+            offline: true,
+            profile: None,
+            profiles: Default::default(),
+            template: None,
+        };
+        let puzzle = Puzzle {
+            year: 2016,
+            day: 7,
+            part: 1,
+        };
+
+        let path = new_day(&root, &config, &puzzle).unwrap();
+        assert_eq!(path, root.join("solutions/2016/src/bin/day7.rs"));
+        assert!(root.join("solutions/2016/build.rs").exists());
+        let manifest = std::fs::read_to_string(root.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("\"solutions/2016\""));
+        assert!(new_day(&root, &config, &puzzle).is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,291 @@
+//! Shortest paths over caller-defined states.  A state is whatever the puzzle needs to
+//! know at a position (direction, run length, keys held...), `successors` lists the states
+//! one move away with the cost of the move, and `goal` says when to stop.
+
+use crate::geom::Dir4;
+use crate::DenseGrid2D;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The states from a start to a goal, both included, and the total cost
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub states: Vec<S>,
+    pub cost: C,
+}
+
+/// States discovered so far, with the move that reached each one most cheaply
+struct Visited<S, C> {
+    states: Vec<S>,
+    parents: Vec<Option<usize>>,
+    best: HashMap<S, (usize, C)>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy + Ord> Visited<S, C> {
+    fn new() -> Self {
+        Visited {
+            states: Vec::new(),
+            parents: Vec::new(),
+            best: HashMap::new(),
+        }
+    }
+
+    /// Record reaching `state` at `cost`, returning its index unless it was already
+    /// reached at least as cheaply
+    fn reach(&mut self, state: S, cost: C, parent: Option<usize>) -> Option<usize> {
+        if self.best.get(&state).is_some_and(|(_, best)| *best <= cost) {
+            return None;
+        }
+        let index = self.states.len();
+        self.states.push(state.clone());
+        self.parents.push(parent);
+        self.best.insert(state, (index, cost));
+        Some(index)
+    }
+
+    /// Whether `index` is still the cheapest way found to its state
+    fn is_current(&self, index: usize) -> bool {
+        self.best[&self.states[index]].0 == index
+    }
+
+    fn path(&self, mut index: usize, cost: C) -> Path<S, C> {
+        let mut states = vec![self.states[index].clone()];
+        while let Some(parent) = self.parents[index] {
+            states.push(self.states[parent].clone());
+            index = parent;
+        }
+        states.reverse();
+        Path { states, cost }
+    }
+}
+
+/// Cheapest path from any of `starts` to a state satisfying `goal`
+pub fn dijkstra<S, C, I, N, NI, G>(starts: I, successors: N, goal: G) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = S>,
+    N: FnMut(&S) -> NI,
+    NI: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    astar(starts, successors, |_| C::default(), goal)
+}
+
+/// Like `dijkstra`, exploring states in order of cost plus `heuristic`, which must never
+/// overestimate the remaining cost for the result to be the cheapest
+pub fn astar<S, C, I, N, NI, H, G>(
+    starts: I,
+    mut successors: N,
+    mut heuristic: H,
+    mut goal: G,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = S>,
+    N: FnMut(&S) -> NI,
+    NI: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let mut visited = Visited::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        let estimate = heuristic(&start);
+        if let Some(index) = visited.reach(start, C::default(), None) {
+            queue.push(Reverse((estimate, C::default(), index)));
+        }
+    }
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        if !visited.is_current(index) {
+            continue;
+        }
+        let state = visited.states[index].clone();
+        if goal(&state) {
+            return Some(visited.path(index, cost));
+        }
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            let estimate = next_cost + heuristic(&next);
+            if let Some(next) = visited.reach(next, next_cost, Some(index)) {
+                queue.push(Reverse((estimate, next_cost, next)));
+            }
+        }
+    }
+    None
+}
+
+/// Cheapest path when every move costs 0 or 1, e.g. the fewest walls to break through
+pub fn bfs_01<S, I, N, NI, G>(starts: I, mut successors: N, mut goal: G) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    N: FnMut(&S) -> NI,
+    NI: IntoIterator<Item = (S, usize)>,
+    G: FnMut(&S) -> bool,
+{
+    let mut visited = Visited::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Some(index) = visited.reach(start, 0, None) {
+            queue.push_back((0, index));
+        }
+    }
+
+    while let Some((cost, index)) = queue.pop_front() {
+        if !visited.is_current(index) {
+            continue;
+        }
+        let state = visited.states[index].clone();
+        if goal(&state) {
+            return Some(visited.path(index, cost));
+        }
+        for (next, step) in successors(&state) {
+            assert!(step <= 1, "bfs_01 moves must cost 0 or 1, not {}", step);
+            if let Some(next) = visited.reach(next, cost + step, Some(index)) {
+                if step == 0 {
+                    queue.push_front((cost, next));
+                } else {
+                    queue.push_back((cost + 1, next));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// A position on a grid with the direction of the last move and how many moves in a row
+/// went that way, for movers that may only turn after or must turn before some run length
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Heading {
+    pub pos: (usize, usize),
+    pub dir: Dir4,
+    pub run: usize,
+}
+
+impl Heading {
+    /// Facing `dir` without having moved, so the first move must go that way
+    pub fn start(pos: (usize, usize), dir: Dir4) -> Self {
+        Heading { pos, dir, run: 0 }
+    }
+
+    /// The headings one move away that keep going straight for at least `min_run` and at
+    /// most `max_run` moves before turning left or right.  Reversing is never allowed.
+    pub fn moves<T>(&self, grid: &DenseGrid2D<T>, min_run: usize, max_run: usize) -> Vec<Heading> {
+        let straight = (self.run < max_run).then_some((self.dir, self.run + 1));
+        let turns = if self.run >= min_run {
+            [
+                Some((self.dir.turn_left(), 1)),
+                Some((self.dir.turn_right(), 1)),
+            ]
+        } else {
+            [None, None]
+        };
+        let pos = self.pos;
+        straight
+            .into_iter()
+            .chain(turns.into_iter().flatten())
+            .filter_map(move |(dir, run)| {
+                grid.offset(pos, dir.delta())
+                    .map(|pos| Heading { pos, dir, run })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2023 day 17
+    const HEAT_LOSS: &str = "2413432311323\n3215453535623\n3255245654254\n3446585845452\n4546657867536\n1438598798454\n4457876987766\n3637877979653\n4654967986887\n4564679986453\n1224686865563\n2546548887735\n4322674655533\n";
+
+    fn crucible(map: &str, min_run: usize, max_run: usize) -> Path<Heading, u32> {
+        let grid = map
+            .parse::<DenseGrid2D<char>>()
+            .unwrap()
+            .map(|c| c.to_digit(10).unwrap());
+        let end = (grid.width() - 1, grid.height() - 1);
+        let starts = [Dir4::East, Dir4::South].map(|dir| Heading::start((0, 0), dir));
+        let heuristic = |h: &Heading| (end.0 - h.pos.0 + end.1 - h.pos.1) as u32;
+        astar(
+            starts,
+            |h| {
+                h.moves(&grid, min_run, max_run)
+                    .into_iter()
+                    .map(|next| (next, grid[next.pos]))
+                    .collect::<Vec<_>>()
+            },
+            heuristic,
+            |h| h.pos == end && h.run >= min_run,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn crucibles() {
+        assert_eq!(crucible(HEAT_LOSS, 0, 3).cost, 102);
+        assert_eq!(crucible(HEAT_LOSS, 4, 10).cost, 94);
+        let ultra = "111111111111\n999999999991\n999999999991\n999999999991\n999999999991\n";
+        assert_eq!(crucible(ultra, 4, 10).cost, 71);
+    }
+
+    #[test]
+    fn reconstructs_path() {
+        let grid = HEAT_LOSS
+            .parse::<DenseGrid2D<char>>()
+            .unwrap()
+            .map(|c| c.to_digit(10).unwrap());
+        let path = crucible(HEAT_LOSS, 0, 3);
+        assert_eq!(path.states[0].pos, (0, 0));
+        assert_eq!(path.states.last().unwrap().pos, (12, 12));
+        let total = path.states[1..].iter().map(|h| grid[h.pos]).sum::<u32>();
+        assert_eq!(total, path.cost);
+        assert!(path.states.iter().all(|h| h.run <= 3));
+
+        // Without a heuristic the cost is the same
+        let end = (12, 12);
+        let plain = dijkstra(
+            [
+                Heading::start((0, 0), Dir4::East),
+                Heading::start((0, 0), Dir4::South),
+            ],
+            |h| {
+                h.moves(&grid, 0, 3)
+                    .into_iter()
+                    .map(|next| (next, grid[next.pos]))
+                    .collect::<Vec<_>>()
+            },
+            |h| h.pos == end,
+        )
+        .unwrap();
+        assert_eq!(plain.cost, 102);
+    }
+
+    #[test]
+    fn zero_one_bfs_counts_walls() {
+        let maze: DenseGrid2D<char> = "S.#..\n##.#.\n...#E\n".parse().unwrap();
+        let start = maze.find(&'S').unwrap();
+        let end = maze.find(&'E').unwrap();
+        let path = bfs_01(
+            [start],
+            |pos| {
+                maze.neighbors4(*pos)
+                    .map(|next| (next, usize::from(maze[next] == '#')))
+                    .collect::<Vec<_>>()
+            },
+            |pos| *pos == end,
+        )
+        .unwrap();
+        assert_eq!(path.cost, 1);
+        assert_eq!(path.states.first(), Some(&start));
+        assert_eq!(path.states.last(), Some(&end));
+
+        let unreachable = dijkstra([0u32], |n| (*n < 10).then_some((n + 2, 1u32)), |n| *n == 5);
+        assert_eq!(unreachable, None);
+    }
+}
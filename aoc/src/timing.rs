@@ -0,0 +1,114 @@
+use anyhow::Result;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Durations collected from one or more runs of the same phase
+#[derive(Debug, Clone)]
+pub struct Timing {
+    samples: Vec<Duration>,
+}
+
+impl Timing {
+    pub fn new(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        Timing { samples }
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples.first().copied().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples.last().copied().unwrap_or_default()
+    }
+
+    pub fn median(&self) -> Duration {
+        let n = self.samples.len();
+        match n {
+            0 => Duration::default(),
+            _ if n % 2 == 1 => self.samples[n / 2],
+            _ => (self.samples[n / 2 - 1] + self.samples[n / 2]) / 2,
+        }
+    }
+
+    pub fn runs(&self) -> usize {
+        self.samples.len()
+    }
+}
+
+impl fmt::Display for Timing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.runs() <= 1 {
+            write!(f, "{}", format_duration(self.min()))
+        } else {
+            write!(
+                f,
+                "min {} / median {} / max {} ({} runs)",
+                format_duration(self.min()),
+                format_duration(self.median()),
+                format_duration(self.max()),
+                self.runs()
+            )
+        }
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    if duration.as_millis() > 0 {
+        format!("{}ms", duration.as_millis())
+    } else if duration.as_micros() > 0 {
+        format!("{}μs", duration.as_micros())
+    } else {
+        format!("{}ns", duration.as_nanos())
+    }
+}
+
+/// Run `f` `repeat` times (at least once), returning the last result and the time of each run
+pub fn time<T, F>(repeat: usize, mut f: F) -> Result<(T, Timing)>
+where
+    F: FnMut() -> Result<T>,
+{
+    let mut samples = Vec::new();
+    let mut result = None;
+    for _ in 0..repeat.max(1) {
+        let now = Instant::now();
+        let value = f()?;
+        samples.push(now.elapsed());
+        result = Some(value);
+    }
+    Ok((result.unwrap(), Timing::new(samples)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_statistics() {
+        let timing = Timing::new(
+            [5, 1, 3, 9]
+                .iter()
+                .map(|ms| Duration::from_millis(*ms))
+                .collect(),
+        );
+        assert_eq!(timing.min(), Duration::from_millis(1));
+        assert_eq!(timing.median(), Duration::from_millis(4));
+        assert_eq!(timing.max(), Duration::from_millis(9));
+        assert_eq!(
+            timing.to_string(),
+            "min 1ms / median 4ms / max 9ms (4 runs)"
+        );
+    }
+
+    #[test]
+    fn time_runs_repeatedly() {
+        let mut calls = 0;
+        let (answer, timing) = time(3, || {
+            calls += 1;
+            Ok(calls)
+        })
+        .unwrap();
+        assert_eq!(answer, 3);
+        assert_eq!(timing.runs(), 3);
+    }
+}
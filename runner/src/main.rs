@@ -0,0 +1,153 @@
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+/// Workspace-wide Advent of Code runner
+#[derive(Parser)]
+#[command(name = "aoc")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run solutions in-process and print a table of answers and timings
+    Run {
+        year: usize,
+
+        /// Only run this day
+        #[arg(long, conflicts_with = "all", required_unless_present = "all")]
+        day: Option<usize>,
+
+        /// Run every day of the year
+        #[arg(long)]
+        all: bool,
+
+        /// Only run this part
+        #[arg(long)]
+        part: Option<u8>,
+
+        /// Run parsing and solving this many times and report min/median/max
+        #[arg(long, default_value_t = 1)]
+        repeat: usize,
+
+        #[command(flatten)]
+        config: aoc::ConfigArgs,
+    },
+
+    /// Show a puzzle's description, downloading it into the cache the first time
+    Show {
+        year: usize,
+        day: usize,
+
+        /// Print the code blocks that may be example inputs instead of the description
+        #[arg(long)]
+        examples: bool,
+
+        /// Download the page again, e.g. to see part two
+        #[arg(long)]
+        refresh: bool,
+
+        #[command(flatten)]
+        config: aoc::ConfigArgs,
+    },
+
+    /// Start a day from the template, creating the year's crate if needed
+    New {
+        year: usize,
+        day: usize,
+
+        #[command(flatten)]
+        config: aoc::ConfigArgs,
+    },
+}
+
+/// The nearest directory with a workspace manifest, starting from the current one
+fn workspace_root() -> Result<PathBuf> {
+    let current = std::env::current_dir()?;
+    current
+        .ancestors()
+        .find(|dir| {
+            std::fs::read_to_string(dir.join("Cargo.toml"))
+                .is_ok_and(|manifest| manifest.contains("[workspace]"))
+        })
+        .map(PathBuf::from)
+        .context("Not inside a cargo workspace")
+}
+
+fn solutions() -> Vec<aoc::Solution> {
+    [
+        aoc2015::SOLUTIONS,
+        aoc2016::SOLUTIONS,
+        aoc2017::SOLUTIONS,
+        aoc2018::SOLUTIONS,
+        aoc2019::SOLUTIONS,
+        aoc2020::SOLUTIONS,
+        aoc2021::SOLUTIONS,
+        aoc2022::SOLUTIONS,
+        aoc2023::SOLUTIONS,
+    ]
+    .concat()
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    match args.command {
+        Command::Run {
+            year,
+            day,
+            all,
+            part,
+            repeat,
+            config,
+        } => {
+            let config = aoc::Config::from_args(&config)?;
+            // clap makes sure exactly one of the two is given
+            let day = if all { None } else { day };
+            let rows =
+                aoc::runner::run_solutions(&solutions(), year, day, part, repeat, |puzzle| {
+                    aoc::get_input_for_puzzle(&config, puzzle)
+                });
+            if rows.is_empty() {
+                bail!("No solutions found for {}", year);
+            }
+            print!("{}", aoc::runner::format_table(&rows));
+            if rows.iter().any(|row| row.outcome.is_err()) {
+                bail!("Some solutions failed");
+            }
+        }
+        Command::Show {
+            year,
+            day,
+            examples,
+            refresh,
+            config,
+        } => {
+            let config = aoc::Config::from_args(&config)?;
+            let puzzle = aoc::Puzzle { year, day, part: 1 };
+            let html = aoc::description::puzzle_page(&config, &puzzle, refresh)?;
+            if examples {
+                for (i, example) in aoc::description::examples(&html).iter().enumerate() {
+                    println!("--- Example {} ---", i + 1);
+                    print!("{}", example);
+                }
+            } else {
+                print!("{}", aoc::description::to_markdown(&html));
+            }
+        }
+        Command::New { year, day, config } => {
+            let config = aoc::Config::from_args(&config)?;
+            let puzzle = aoc::Puzzle { year, day, part: 1 };
+            let path = aoc::scaffold::new_day(&workspace_root()?, &config, &puzzle)?;
+            println!("Created {}", path.display());
+            let known = solutions()
+                .iter()
+                .any(|solution| solution.puzzle.year == year);
+            if !known {
+                println!("Add aoc{} to the runner's dependencies and solutions() to include it in `aoc run`", year);
+            }
+        }
+    }
+    Ok(())
+}
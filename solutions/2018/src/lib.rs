@@ -0,0 +1,4 @@
+//! Every solution binary of this year, compiled as a module so the workspace runner can
+//! call them in-process.  The module list is generated by `build.rs`.
+
+include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
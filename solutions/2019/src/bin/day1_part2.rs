@@ -1,4 +1,4 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
 
 #[aoc::main]
 fn solve(input: &str) -> Result<i64> {
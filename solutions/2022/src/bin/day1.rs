@@ -1,19 +1,11 @@
 use anyhow::{bail, Result};
 
-#[aoc::main]
-fn solve(input: &str) -> Result<u32> {
-    let vals: Vec<u32> = aoc::parse_list(input)?;
-    let mut answer = 0;
-    for num1 in &vals {}
-
-    bail!("No solution found")
+// Examples are checked once they have answers: `example(input = ..., part1 = ...)`
+#[aoc::main(part1)]
+fn parse(input: &str) -> Result<Vec<String>> {
+    Ok(input.lines().map(String::from).collect())
 }
 
-fn tests() -> anyhow::Result<()> {
-    let input = "";
-
-    let solution = solve(input)?;
-
-    assert_eq!(solution, 314159);
-    Ok(())
+fn part1(lines: &[String]) -> Result<usize> {
+    bail!("No solution found for {} lines", lines.len())
 }
@@ -3,7 +3,6 @@ use regex::Regex;
 use std::cmp;
 use std::collections::HashSet;
 use std::collections::VecDeque;
-use std::env;
 
 #[derive(Clone, Debug)]
 struct Blueprint {
@@ -102,12 +101,12 @@ fn solve_blueprint(blueprint: &Blueprint) -> u16 {
                 if delay < state.minutes_left {
                     let mut state_ = state.clone();
                     state_.geode_robots += 1;
-                    state_.minutes_left = state_.minutes_left - delay;
+                    state_.minutes_left -= delay;
                     state_.obsidian = state_.obsidian + (delay * state.obsidian_robots)
                         - blueprint.geode_obsidian;
                     state_.ore = state_.ore + (delay * state.ore_robots) - blueprint.geode_ore;
-                    state_.clay = state_.clay + (delay * state.clay_robots);
-                    state_.geodes = state_.geodes + (delay * state.geode_robots);
+                    state_.clay += delay * state.clay_robots;
+                    state_.geodes += delay * state.geode_robots;
                     deque.push_back(state_);
                 }
             }
@@ -129,12 +128,12 @@ fn solve_blueprint(blueprint: &Blueprint) -> u16 {
                 if delay < state.minutes_left {
                     let mut state_ = state.clone();
                     state_.obsidian_robots += 1;
-                    state_.minutes_left = state_.minutes_left - delay;
-                    state_.obsidian = state_.obsidian + (delay * state.obsidian_robots);
+                    state_.minutes_left -= delay;
+                    state_.obsidian += delay * state.obsidian_robots;
                     state_.ore = state_.ore + (delay * state.ore_robots) - blueprint.obsidian_ore;
                     state_.clay =
                         state_.clay + (delay * state.clay_robots) - blueprint.obsidian_clay;
-                    state_.geodes = state_.geodes + (delay * state.geode_robots);
+                    state_.geodes += delay * state.geode_robots;
                     deque.push_back(state_);
                 }
             }
@@ -152,11 +151,11 @@ fn solve_blueprint(blueprint: &Blueprint) -> u16 {
                 if delay < state.minutes_left {
                     let mut state_ = state.clone();
                     state_.clay_robots += 1;
-                    state_.minutes_left = state_.minutes_left - delay;
-                    state_.obsidian = state_.obsidian + (delay * state.obsidian_robots);
+                    state_.minutes_left -= delay;
+                    state_.obsidian += delay * state.obsidian_robots;
                     state_.ore = state_.ore + (delay * state.ore_robots) - blueprint.clay_ore;
-                    state_.clay = state_.clay + (delay * state.clay_robots);
-                    state_.geodes = state_.geodes + (delay * state.geode_robots);
+                    state_.clay += delay * state.clay_robots;
+                    state_.geodes += delay * state.geode_robots;
                     deque.push_back(state_);
                 }
             }
@@ -178,11 +177,11 @@ fn solve_blueprint(blueprint: &Blueprint) -> u16 {
                 if delay < state.minutes_left {
                     let mut state_ = state.clone();
                     state_.ore_robots += 1;
-                    state_.minutes_left = state_.minutes_left - delay;
-                    state_.obsidian = state_.obsidian + (delay * state.obsidian_robots);
+                    state_.minutes_left -= delay;
+                    state_.obsidian += delay * state.obsidian_robots;
                     state_.ore = state_.ore + (delay * state.ore_robots) - blueprint.ore_ore;
-                    state_.clay = state_.clay + (delay * state.clay_robots);
-                    state_.geodes = state_.geodes + (delay * state.geode_robots);
+                    state_.clay += delay * state.clay_robots;
+                    state_.geodes += delay * state.geode_robots;
                     deque.push_back(state_);
                 }
             }
@@ -202,14 +201,14 @@ fn solve_blueprint(blueprint: &Blueprint) -> u16 {
     }
 
     // println!("{blueprint:?}");
-    return max;
+    max
 }
 
 fn div_ceil(i: u16, j: u16) -> u16 {
-    if i % j == 0 {
-        return i / j;
+    if i.is_multiple_of(j) {
+        i / j
     } else {
-        return (i / j) + 1;
+        (i / j) + 1
     }
 }
 
@@ -217,11 +216,10 @@ fn div_ceil(i: u16, j: u16) -> u16 {
 fn solve(input: &str) -> Result<u16> {
     let mut blueprints: Vec<Blueprint> = Vec::new();
 
+    let re = Regex::new(r"^Blueprint (\d+): Each ore robot costs (\d+) ore. Each clay robot costs (\d+) ore. Each obsidian robot costs (\d+) ore and (\d+) clay. Each geode robot costs (\d+) ore and (\d+) obsidian.$")
+        .expect("Regex Error");
     for line in aoc::parse_list::<String>(input)? {
-        let caps = Regex::new(r"^Blueprint (\d+): Each ore robot costs (\d+) ore. Each clay robot costs (\d+) ore. Each obsidian robot costs (\d+) ore and (\d+) clay. Each geode robot costs (\d+) ore and (\d+) obsidian.$")
-            .expect("Regex Error")
-            .captures(&line)
-            .unwrap();
+        let caps = re.captures(&line).unwrap();
 
         blueprints.push(Blueprint {
             ordinal: caps[1].parse::<u16>().unwrap(),
@@ -1,9 +1,8 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
 
 #[aoc::main]
 fn solve(input: &str) -> Result<u32> {
     let vals: Vec<String> = aoc::parse_list(input)?;
-    let mut answer = 0;
 
     println!("{:?}", vals);
 
@@ -1,6 +1,5 @@
 use anyhow::Result;
 use itertools::Itertools;
-use std::collections::HashMap;
 use Direction::*;
 
 #[derive(Debug, Clone, PartialEq, Copy)]
@@ -22,7 +21,7 @@ fn solve(input: &str) -> Result<i64> {
     // Find starting position
     let mut start_pos = (0, 0);
     'START_SEARCH: for (j, row) in map.iter().enumerate() {
-        for (i, cell) in map[j].iter().enumerate() {
+        for (i, _) in map[j].iter().enumerate() {
             if row[i] == 'S' {
                 start_pos = (i, j);
                 break 'START_SEARCH;
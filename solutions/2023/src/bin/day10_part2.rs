@@ -1,6 +1,5 @@
 use anyhow::Result;
 use itertools::Itertools;
-use std::collections::HashMap;
 use Direction::*;
 
 #[derive(Debug, Clone, PartialEq, Copy)]
@@ -21,7 +20,7 @@ fn solve(input: &str) -> Result<usize> {
 
     // Find starting position
     let mut start_pos = (0, 0);
-    'START_SEARCH: for (j, row) in map.iter().enumerate() {
+    'START_SEARCH: for (j, _) in map.iter().enumerate() {
         for (i, cell) in map[j].iter().enumerate() {
             if *cell == 'S' {
                 start_pos = (i, j);
@@ -85,10 +84,10 @@ fn mark_all_reachable_points(path_map: &[Vec<char>]) -> Vec<Vec<char>> {
     let map_clone = map.to_vec();
     for (j, row) in map.iter_mut().enumerate() {
         for (i, c) in row.iter_mut().enumerate() {
-            if j == 0 || i == 0 || j == row_count - 1 || i == col_count - 1 {
-                if *c == '.' || (*c == '*' && can_squeeze_through(i, j, &map_clone)) {
-                    *c = 'O';
-                }
+            if (j == 0 || i == 0 || j == row_count - 1 || i == col_count - 1)
+                && (*c == '.' || (*c == '*' && can_squeeze_through(i, j, &map_clone)))
+            {
+                *c = 'O';
             }
         }
     }
@@ -99,7 +98,7 @@ fn mark_all_reachable_points(path_map: &[Vec<char>]) -> Vec<Vec<char>> {
             for (i, c) in row.iter().enumerate() {
                 if *c == 'O' {
                     for (x, y) in [(0, 1), (0, -1), (1, 0), (-1, 0)] {
-                        let (x, y) = (i + x as usize, j + y as usize);
+                        let (x, y) = (i.wrapping_add(x as usize), j.wrapping_add(y as usize));
                         if let Some(c) = map.get(y).and_then(|row| row.get(x)) {
                             if *c == '.' || (*c == '*' && can_squeeze_through(x, y, &map)) {
                                 updates.push((x, y));
@@ -139,7 +138,10 @@ fn get_neighbor(
         West => (-1, 0),
     };
 
-    let (x, y) = (x + diff.0 as usize, y + diff.1 as usize);
+    let (x, y) = (
+        x.wrapping_add(diff.0 as usize),
+        y.wrapping_add(diff.1 as usize),
+    );
     if let Some(c) = map.get(y).and_then(|row| row.get(x)) {
         return Some((x, y, *c));
     }
@@ -168,6 +170,7 @@ fn can_squeeze_through(x: usize, y: usize, map: &[Vec<char>]) -> bool {
     true
 }
 
+#[allow(dead_code)]
 fn print_map(map: &[Vec<char>]) {
     for row in map {
         for c in row {
@@ -190,7 +193,7 @@ fn get_path_size(
 
     let mut path_map = map
         .iter()
-        .map(|row| row.iter().map(|x| '.').collect_vec())
+        .map(|row| row.iter().map(|_| '.').collect_vec())
         .collect_vec();
 
     println!("Starting at {:?}, {start_char}", pos);
@@ -213,13 +216,13 @@ fn get_path_size(
 }
 
 fn can_enter(c: char, d: Direction) -> bool {
-    match (c, d) {
-        ('|' | '7' | 'F', South) => true,
-        ('|' | 'L' | 'J', North) => true,
-        ('-' | 'J' | '7', West) => true,
-        ('-' | 'L' | 'F', East) => true,
-        _ => false,
-    }
+    matches!(
+        (c, d),
+        ('|' | '7' | 'F', South)
+            | ('|' | 'L' | 'J', North)
+            | ('-' | 'J' | '7', West)
+            | ('-' | 'L' | 'F', East)
+    )
 }
 
 fn next_pos(
@@ -1,15 +1,5 @@
 use anyhow::Result;
 use itertools::Itertools;
-use std::collections::HashMap;
-use Direction::*;
-
-#[derive(Debug, Clone, PartialEq, Copy)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
 
 #[aoc::main]
 fn solve(input: &str) -> Result<usize> {
@@ -1,15 +1,5 @@
 use anyhow::Result;
 use itertools::Itertools;
-use std::collections::HashMap;
-use Direction::*;
-
-#[derive(Debug, Clone, PartialEq, Copy)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
 
 #[aoc::main]
 fn solve(input: &str) -> Result<usize> {
@@ -90,8 +80,8 @@ fn get_galaxy_positions(map: &[Vec<char>]) -> Vec<(usize, usize)> {
 fn get_expanded_rows(map: &[Vec<char>]) -> Vec<usize> {
     map.iter()
         .enumerate()
-        .filter(|(j, row)| row.iter().all(|x| *x == '.'))
-        .map(|(j, row)| j)
+        .filter(|(_, row)| row.iter().all(|x| *x == '.'))
+        .map(|(j, _)| j)
         .collect_vec()
 }
 
@@ -107,6 +97,7 @@ fn get_expanded_cols(map: &[Vec<char>]) -> Vec<usize> {
 
 // fn get_expanded_columns(map: &[Vec<char>]) -> Vec<usize> {}
 
+#[allow(dead_code)]
 fn expand_map(map: &[Vec<char>]) -> Vec<Vec<char>> {
     let mut new_map = Vec::new();
 
@@ -1,14 +1,5 @@
 use anyhow::Result;
 use itertools::Itertools;
-use std::collections::HashMap;
-use SpringState::*;
-
-#[derive(Debug, Clone)]
-enum SpringState {
-    Operational,
-    Broken,
-    Unknown,
-}
 
 #[derive(Debug, Clone)]
 struct Input {
@@ -59,7 +50,7 @@ fn count_valid_combinations(input: &Input) -> usize {
             }
         }
     } else {
-        if is_valid_combination(&input) {
+        if is_valid_combination(input) {
             combinations += 1;
         }
     }
@@ -69,7 +60,7 @@ fn count_valid_combinations(input: &Input) -> usize {
 }
 
 fn is_possible_combination(input: &Input) -> bool {
-    let mut g = input.groups.clone();
+    let g = input.groups.clone();
 
     let s = input.springs.iter().collect::<String>();
     let splits = s.split('.').filter(|x| !x.is_empty()).collect_vec();
@@ -96,7 +87,7 @@ fn is_possible_combination(input: &Input) -> bool {
 }
 
 fn is_valid_combination(input: &Input) -> bool {
-    let mut g = input.groups.clone();
+    let g = input.groups.clone();
 
     let s = input.springs.iter().collect::<String>();
     let splits = s.split('.').filter(|x| !x.is_empty()).collect_vec();
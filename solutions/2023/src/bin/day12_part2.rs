@@ -1,7 +1,6 @@
 use anyhow::Result;
 use cached::proc_macro::cached;
 use itertools::Itertools;
-use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq, Hash, Eq)]
 struct Input {
@@ -25,7 +24,7 @@ fn solve(input: &str) -> Result<usize> {
 
         let springs_orig = l[0].chars().collect_vec();
         let mut springs = springs_orig.clone();
-        for i in 0..4 {
+        for _ in 0..4 {
             springs.push('?');
             springs.extend(springs_orig.clone());
         }
@@ -34,7 +33,7 @@ fn solve(input: &str) -> Result<usize> {
             .iter()
             .cycle()
             .take(groups.len() * 5)
-            .map(|c| *c)
+            .copied()
             .collect_vec();
 
         states.push(Input { springs, groups });
@@ -83,11 +82,9 @@ fn shrink_input(input: &Input) -> Input {
     let s = input.springs.iter().collect::<String>();
     let splits = s.split('.').filter(|x| !x.is_empty()).collect_vec();
 
-    for split in &splits {}
-
     let mut si = 0;
     'SPLITS: for (split_idx, split) in splits.iter().enumerate() {
-        for (i, c) in split.chars().enumerate() {
+        for c in split.chars() {
             if c == '?' {
                 si = split_idx;
                 break 'SPLITS;
@@ -100,7 +97,7 @@ fn shrink_input(input: &Input) -> Input {
     }
 
     let springs = splits.iter().skip(si).join(".").chars().collect_vec();
-    let groups = input.groups.iter().skip(si).map(|c| *c).collect_vec();
+    let groups = input.groups.iter().skip(si).copied().collect_vec();
 
     let out = Input { springs, groups };
     // println!("Shrunk {:?} to {:?}", input, out);
@@ -108,7 +105,7 @@ fn shrink_input(input: &Input) -> Input {
 }
 
 fn is_possible_combination(input: &Input) -> bool {
-    let mut g = input.groups.clone();
+    let g = input.groups.clone();
 
     let s = input.springs.iter().collect::<String>();
     let splits = s.split('.').filter(|x| !x.is_empty()).collect_vec();
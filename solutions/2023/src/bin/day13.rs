@@ -1,6 +1,5 @@
 use anyhow::Result;
 use itertools::Itertools;
-use std::collections::HashMap;
 
 #[aoc::main]
 fn solve(input: &str) -> Result<usize> {
@@ -42,8 +41,6 @@ fn solve(input: &str) -> Result<usize> {
 }
 
 fn find_vertical_reflextion(map: &[Vec<char>]) -> Option<usize> {
-    let i = 0;
-
     let width = map[0].len();
 
     'COL: for col in 0..width - 1 {
@@ -67,8 +64,6 @@ fn find_vertical_reflextion(map: &[Vec<char>]) -> Option<usize> {
 }
 
 fn find_horizontal_reflextion(map: &[Vec<char>]) -> Option<usize> {
-    let i = 0;
-
     let height = map.len();
 
     'ROW: for row in 0..map.len() - 1 {
@@ -1,6 +1,5 @@
 use anyhow::Result;
 use itertools::Itertools;
-use std::collections::HashMap;
 use Orientation::*;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -26,7 +25,7 @@ fn solve(input: &str) -> Result<usize> {
 
     let mut answer = 0;
     'PUZZLE: for puzzle in puzzles {
-        let mut orig_reflextion = (0, Vertical);
+        let orig_reflextion;
 
         if let Some(index) = find_vertical_reflextion(&puzzle, None) {
             orig_reflextion = (index, Vertical);
@@ -89,8 +88,6 @@ fn solve(input: &str) -> Result<usize> {
 }
 
 fn find_vertical_reflextion(map: &[Vec<char>], skip_index: Option<usize>) -> Option<usize> {
-    let i = 0;
-
     let width = map[0].len();
 
     'COL: for col in 0..width - 1 {
@@ -120,8 +117,6 @@ fn find_vertical_reflextion(map: &[Vec<char>], skip_index: Option<usize>) -> Opt
 }
 
 fn find_horizontal_reflextion(map: &[Vec<char>], skip_index: Option<usize>) -> Option<usize> {
-    let i = 0;
-
     let height = map.len();
 
     'ROW: for row in 0..map.len() - 1 {
@@ -1,6 +1,8 @@
+// Rocks roll along columns as well as rows, which reads best with plain indices
+#![allow(clippy::needless_range_loop)]
+
 use anyhow::Result;
 use itertools::Itertools;
-use std::collections::HashMap;
 
 #[aoc::main]
 fn solve(input: &str) -> Result<usize> {
@@ -17,25 +19,18 @@ fn solve(input: &str) -> Result<usize> {
     let map = tilt_map(&map);
     print_map(&map);
 
-    let mut answer = calculate_load(&map);
+    let answer = calculate_load(&map);
 
     Ok(answer)
 }
 
 fn calculate_load(map: &[Vec<char>]) -> usize {
-    let width = map[0].len();
     let height = map.len();
 
-    let mut load = 0;
-    for j in 0..height {
-        for i in 0..width {
-            if map[j][i] == 'O' {
-                load += height - j;
-            }
-        }
-    }
-
-    load
+    map.iter()
+        .enumerate()
+        .map(|(j, row)| row.iter().filter(|c| **c == 'O').count() * (height - j))
+        .sum()
 }
 
 fn tilt_map(map: &[Vec<char>]) -> Vec<Vec<char>> {
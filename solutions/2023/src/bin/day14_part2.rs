@@ -1,3 +1,6 @@
+// Rocks roll along columns as well as rows, which reads best with plain indices
+#![allow(clippy::needless_range_loop)]
+
 use anyhow::Result;
 use itertools::Itertools;
 
@@ -18,17 +21,18 @@ fn solve(input: &str) -> Result<usize> {
         loads.push(calculate_load(&map));
     }
 
-    // Calculate cycle length
+    // Calculate cycle length: the shortest one the last `depth` loads all repeat with, as
+    // a single load can come up twice within one cycle
+    let depth = 10;
     let mut interval = 999999999;
-    'INTERVAL: for i in 1..1000 {
-        let depth = 10;
+    'INTERVAL: for i in 1..=(loads.len() - depth) {
         for d in 0..depth {
             if loads[loads.len() - 1 - d - i] != loads[loads.len() - 1 - d] {
                 continue 'INTERVAL;
             }
-            interval = i;
-            break 'INTERVAL;
         }
+        interval = i;
+        break;
     }
 
     // dbg!(interval);
@@ -48,19 +52,12 @@ fn solve(input: &str) -> Result<usize> {
 }
 
 fn calculate_load(map: &[Vec<char>]) -> usize {
-    let width = map[0].len();
     let height = map.len();
 
-    let mut load = 0;
-    for j in 0..height {
-        for i in 0..width {
-            if map[j][i] == 'O' {
-                load += height - j;
-            }
-        }
-    }
-
-    load
+    map.iter()
+        .enumerate()
+        .map(|(j, row)| row.iter().filter(|c| **c == 'O').count() * (height - j))
+        .sum()
 }
 
 fn tilt_map_north(map: &[Vec<char>]) -> Vec<Vec<char>> {
@@ -120,7 +117,7 @@ fn tilt_map_south(map: &[Vec<char>]) -> Vec<Vec<char>> {
 }
 
 fn cycle(map: &[Vec<char>]) -> Vec<Vec<char>> {
-    let map = tilt_map_north(&map);
+    let map = tilt_map_north(map);
     let map = tilt_map_west(&map);
     let map = tilt_map_south(&map);
     tilt_map_east(&map)
@@ -12,7 +12,7 @@ fn solve(input: &str) -> Result<usize> {
     let input = input.replace('\n', "");
     let inputs = input.split(',').collect_vec();
 
-    let mut boxes: Vec<Vec<Entry>> = std::iter::repeat(vec![]).take(256).collect::<Vec<_>>();
+    let mut boxes: Vec<Vec<Entry>> = std::iter::repeat_n(vec![], 256).collect::<Vec<_>>();
 
     'INPUT: for input in inputs {
         if input.ends_with('-') {
@@ -32,7 +32,7 @@ struct Tile {
 
 #[aoc::main]
 fn solve(input: &str) -> Result<usize> {
-    let mut map = aoc::parse_list::<String>(input)?
+    let map = aoc::parse_list::<String>(input)?
         .iter()
         .map(|x| {
             x.chars()
@@ -52,8 +52,6 @@ fn solve(input: &str) -> Result<usize> {
 }
 
 fn calculate_energized_tiles(map: &[Vec<Tile>]) -> usize {
-    let width = map[0].len();
-    let height = map.len();
 
     let mut map = map.to_vec();
     energize_tiles(&mut map);
@@ -22,7 +22,7 @@ struct Pos {
 }
 
 impl Pos {
-    fn neighbors(&self, map: &Vec<Vec<u32>>) -> Vec<(Pos, u32)> {
+    fn neighbors(&self, map: &[Vec<u32>]) -> Vec<(Pos, u32)> {
         let mut neighbors: Vec<(Pos, u32)> = Vec::new();
 
         let moves = match self.direction {
@@ -79,7 +79,7 @@ fn solve(input: &str) -> Result<u32> {
     let heuristic_map = get_heuristic_map(&map);
     let heuristic_map_elapsed = heuristic_map_start.elapsed().as_millis();
 
-    let starts = vec![
+    let starts = [
         Pos {
             row: 0,
             col: 0,
@@ -125,7 +125,7 @@ fn solve(input: &str) -> Result<u32> {
     Ok(answer)
 }
 
-fn get_heuristic_map(map: &Vec<Vec<u32>>) -> HashMap<(usize, usize), ((usize, usize), usize)> {
+fn get_heuristic_map(map: &[Vec<u32>]) -> HashMap<(usize, usize), ((usize, usize), usize)> {
     let rows = map.len();
     let cols = map[0].len();
 
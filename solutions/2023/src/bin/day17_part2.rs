@@ -21,7 +21,7 @@ struct Pos {
 }
 
 impl Pos {
-    fn neighbors(&self, map: &Vec<Vec<u32>>) -> Vec<(Pos, u32)> {
+    fn neighbors(&self, map: &[Vec<u32>]) -> Vec<(Pos, u32)> {
         let mut neighbors: Vec<(Pos, u32)> = Vec::new();
 
         let moves = match self.direction {
@@ -138,7 +138,7 @@ fn solve(input: &str) -> Result<u32> {
     // There's a subtlety here.  The start going east will immediately
     // add nodes going south from neighbors and vice versa.  This is
     // awkward, but it ends up working out properly.
-    let starts = vec![
+    let starts = [
         Pos {
             row: 0,
             col: 0,
@@ -185,7 +185,7 @@ fn solve(input: &str) -> Result<u32> {
     Ok(answer)
 }
 
-fn get_heuristic_map(map: &Vec<Vec<u32>>) -> Vec<Vec<u32>> {
+fn get_heuristic_map(map: &[Vec<u32>]) -> Vec<Vec<u32>> {
     let rows = map.len();
     let cols = map[0].len();
 
@@ -1,6 +1,5 @@
 use anyhow::Result;
 use itertools::Itertools;
-use pathfinding::prelude::astar;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
@@ -63,7 +62,7 @@ fn solve(input: &str) -> Result<usize> {
     );
 
     for instruction in &instructions {
-        for i in 1..=instruction.distance {
+        for _ in 1..=instruction.distance {
             let mut new_pos = pos.clone();
             match instruction.direction {
                 North => new_pos.row -= 1,
@@ -120,8 +119,6 @@ fn get_dimensitons(map: &HashMap<Pos, Cell>) -> (Pos, Pos) {
 
 fn get_contained_size(map: &mut HashMap<Pos, Cell>) -> usize {
     let (min, max) = get_dimensitons(map);
-    let width = max.col - min.col + 1;
-    let height = max.row - min.row + 1;
 
     // let mut seen = HashMap::new();
 
@@ -177,8 +174,6 @@ fn print_sparse_map(map: &HashMap<Pos, Cell>) {
     // Shift the positions to make everything positive and convert to a dense map
     println!("{min:?} to {max:?}");
 
-    let width = max.col - min.col + 1;
-    let height = max.row - min.row + 1;
 
     for row in min.row..=max.row {
         for col in min.col..=max.col {
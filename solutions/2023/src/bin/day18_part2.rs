@@ -1,6 +1,5 @@
 use anyhow::Result;
 use itertools::Itertools;
-use pathfinding::prelude::astar;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
@@ -127,14 +126,13 @@ fn solve(input: &str) -> Result<usize> {
     Ok(answer)
 }
 
+#[allow(dead_code)]
 fn print_sparse_map(map: &HashMap<Pos, Cell>) {
     let (min, max) = get_dimensitons(map);
 
     // Shift the positions to make everything positive and convert to a dense map
     println!("{min:?} to {max:?}");
 
-    let width = max.col - min.col + 1;
-    let height = max.row - min.row + 1;
 
     for row in min.row..=max.row {
         for col in min.col..=max.col {
@@ -198,12 +196,10 @@ fn get_dimensitons(map: &HashMap<Pos, Cell>) -> (Pos, Pos) {
 
 fn get_contained_size(
     map: &mut HashMap<Pos, Cell>,
-    col_vals: &Vec<isize>,
-    row_vals: &Vec<isize>,
+    col_vals: &[isize],
+    row_vals: &[isize],
 ) -> usize {
     let (min, max) = get_dimensitons(map);
-    let width = max.col - min.col + 1;
-    let height = max.row - min.row + 1;
 
     // let mut seen = HashMap::new();
 
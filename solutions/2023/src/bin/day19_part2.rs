@@ -9,14 +9,6 @@ use PartDecision::*;
 use Rule::*;
 use Target::*;
 
-#[derive(Clone, Debug, Hash, Eq, PartialEq)]
-struct Part {
-    x: usize,
-    m: usize,
-    a: usize,
-    s: usize,
-}
-
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 struct Range {
     min: usize,
@@ -102,7 +102,7 @@ fn solve(input: &str) -> Result<usize> {
 
     let mut low_pulses = 0;
     let mut high_pulses = 0;
-    for i in 0..1000 {
+    for _ in 0..1000 {
         let (h, l) = perform_button_press(&mut modules);
         low_pulses += l;
         high_pulses += h;
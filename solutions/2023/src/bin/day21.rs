@@ -1,7 +1,5 @@
 use anyhow::Result;
 use itertools::Itertools;
-use lazy_regex::regex_captures;
-use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 
@@ -26,6 +24,7 @@ fn solve(input: &str) -> Result<usize> {
     Ok(answer)
 }
 
+#[allow(dead_code)]
 fn print_map(map: &[Vec<char>]) {
     for row in map {
         for c in row {
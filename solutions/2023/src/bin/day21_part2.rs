@@ -10,12 +10,6 @@ struct Pos {
     col: usize,
 }
 
-#[derive(Clone, Debug, Hash, Eq, PartialEq)]
-struct IPos {
-    row: isize,
-    col: isize,
-}
-
 #[aoc::main]
 fn solve(input: &str) -> Result<usize> {
     part2(input, 26501365)
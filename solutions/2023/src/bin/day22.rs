@@ -1,9 +1,6 @@
 use anyhow::Result;
 use itertools::Itertools;
-use lazy_regex::regex_captures;
 use std::collections::HashMap;
-use std::collections::HashSet;
-use std::collections::VecDeque;
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 struct Range {
@@ -132,7 +129,7 @@ fn solve(input: &str) -> Result<usize> {
 
 fn can_be_disintegrated(
     brick: &Brick,
-    bricks: &[Brick],
+    _bricks: &[Brick],
     occupied: &HashMap<(usize, usize, usize), Brick>,
 ) -> bool {
     // Sanity check
@@ -1,9 +1,7 @@
 use anyhow::Result;
 use itertools::Itertools;
-use lazy_regex::regex_captures;
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::collections::VecDeque;
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 struct Range {
@@ -1,18 +1,7 @@
 use anyhow::Result;
 use itertools::Itertools;
-use lazy_regex::regex_captures;
-use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
-use Direction::*;
-
-#[derive(Debug, Clone, PartialEq, Copy)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
 
 #[derive(Debug, Clone, PartialEq)]
 struct Hike {
@@ -35,7 +24,6 @@ impl Hike {
             _ => vec![[0, 1], [0, -1], [1, 0], [-1, 0]],
         };
 
-        let (i, j) = (last_pos.col as isize, last_pos.row as isize);
         for [di, dj] in possible_neighbors {
             let (new_row, new_col) = (last_pos.row as isize + dj, last_pos.col as isize + di);
             if new_row >= 0 && new_col >= 0 {
@@ -100,6 +88,7 @@ fn solve(input: &str) -> Result<usize> {
     Ok(answer)
 }
 
+#[allow(dead_code)]
 fn print_map(map: &[Vec<char>]) {
     for row in map {
         for c in row {
@@ -109,6 +98,7 @@ fn print_map(map: &[Vec<char>]) {
     }
 }
 
+#[allow(dead_code)]
 fn print_hike(hike: &Hike, map: &[Vec<char>]) {
     for (j, row) in map.iter().enumerate() {
         for (i, c) in row.iter().enumerate() {
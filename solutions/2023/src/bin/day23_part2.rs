@@ -71,14 +71,14 @@ fn simplify_graph(graph: &UndirectedGraph, ignore_nodes: HashSet<String>) -> Und
                 if neighbors.len() == 2 && !ignore_nodes.contains(&name) {
                     if let Some(((n1, c1), (n2, c2))) = neighbors.iter().collect_tuple() {
                         if !ignore_nodes.contains(n1) && !ignore_nodes.contains(n2) {
-                            graph.neighbors.get_mut(n1).map(|m| {
+                            if let Some(m) = graph.neighbors.get_mut(n1) {
                                 m.insert(n2.to_string(), c1 + c2);
                                 m.remove(&name);
-                            });
-                            graph.neighbors.get_mut(n2).map(|m| {
+                            }
+                            if let Some(m) = graph.neighbors.get_mut(n2) {
                                 m.insert(n1.to_string(), c1 + c2);
                                 m.remove(&name);
-                            });
+                            }
 
                             graph.neighbors.remove(&name);
                             graph.nodes.remove(&GraphNode { name });
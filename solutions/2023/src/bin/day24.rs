@@ -1,8 +1,5 @@
 use anyhow::Result;
 use itertools::Itertools;
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::collections::VecDeque;
 
 #[derive(Debug, Clone, PartialEq)]
 struct HailStone {
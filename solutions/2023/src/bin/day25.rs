@@ -38,13 +38,13 @@ fn solve(input: &str) -> Result<usize> {
                 graph
                     .neighbors
                     .entry(n1.to_string())
-                    .or_insert(HashMap::new())
+                    .or_default()
                     .insert(n2.to_string(), 1);
 
                 graph
                     .neighbors
                     .entry(n2.to_string())
-                    .or_insert(HashMap::new())
+                    .or_default()
                     .insert(n1.to_string(), 1);
 
                 graph.nodes.insert(GraphNode {
@@ -1,5 +1,4 @@
 use anyhow::Result;
-use aoc::Searchable;
 use itertools::Itertools;
 
 #[aoc::main]
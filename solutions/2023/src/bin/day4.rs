@@ -1,6 +1,4 @@
 use anyhow::Result;
-use aoc::Searchable;
-use itertools::Itertools;
 use lazy_regex::regex_captures;
 use std::collections::HashSet;
 
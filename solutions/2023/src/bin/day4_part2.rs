@@ -1,6 +1,4 @@
 use anyhow::Result;
-use aoc::Searchable;
-use itertools::Itertools;
 use lazy_regex::regex_captures;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -45,7 +43,7 @@ fn solve(input: &str) -> Result<u64> {
     let mut cache: HashMap<u32, u64> = HashMap::new();
 
     for card in &cards {
-        answer += get_card_count(&mut cache, &cards, &card);
+        answer += get_card_count(&mut cache, &cards, card);
         // let winners = card
         //     .your_numbers
         //     .iter()
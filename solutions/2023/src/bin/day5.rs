@@ -72,7 +72,7 @@ fn lookup(x: &u64, ranges: &Vec<Range>) -> u64 {
     *x
 }
 
-fn parse_map(lines: &Vec<String>, i: &mut usize) -> Vec<Range> {
+fn parse_map(lines: &[String], i: &mut usize) -> Vec<Range> {
     let mut ranges = Vec::new();
 
     // Skip the header line
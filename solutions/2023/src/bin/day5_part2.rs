@@ -110,6 +110,8 @@ fn compress_coordinates(orig: Vec<u64>) -> CompressedCoordinates {
     }
 }
 */
+// One map per step of the almanac
+#[allow(clippy::too_many_arguments)]
 fn get_location(
     seed: &u64,
     seed_to_soil_map: &Vec<Range>,
@@ -152,7 +154,7 @@ fn lookup(x: &u64, ranges: &Vec<Range>) -> u64 {
     *x
 }
 
-fn parse_map(lines: &Vec<String>, i: &mut usize) -> Vec<Range> {
+fn parse_map(lines: &[String], i: &mut usize) -> Vec<Range> {
     let mut ranges = Vec::new();
 
     // Skip the header line
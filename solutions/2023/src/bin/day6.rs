@@ -18,7 +18,7 @@ fn solve(input: &str) -> Result<u64> {
 
     let mut answer = 1;
     for i in 0..times.len() {
-        answer = answer * ways_to_win(&times[i], &distances[i]);
+        answer *= ways_to_win(&times[i], &distances[i]);
     }
 
     println!("{:?}, {:?}", times, distances);
@@ -1,9 +1,19 @@
 use anyhow::Result;
 use itertools::Itertools;
-use std::collections::HashMap;
 
-#[aoc::main]
-fn solve(input: &str) -> Result<i64> {
+#[aoc::main(
+    part1,
+    part2,
+    example(
+        input = "0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+",
+        part1 = 114,
+        part2 = 2
+    )
+)]
+fn parse(input: &str) -> Result<Vec<Vec<i64>>> {
     let sequences = aoc::parse_list::<String>(input)?
         .iter()
         .map(|x| {
@@ -13,12 +23,14 @@ fn solve(input: &str) -> Result<i64> {
         })
         .collect_vec();
 
-    println!("{:?}", sequences);
+    Ok(sequences)
+}
 
+fn part1(sequences: &[Vec<i64>]) -> Result<i64> {
     let mut answer = 0;
     for sequence in sequences {
         let mut last_nums = Vec::new();
-        let mut sequence = sequence;
+        let mut sequence = sequence.clone();
         while !sequence.iter().all(|x| *x == 0) {
             let next_sequence = get_next_sequence(&sequence);
             last_nums.push(*sequence.last().unwrap());
@@ -36,19 +48,28 @@ fn solve(input: &str) -> Result<i64> {
     Ok(answer)
 }
 
-fn get_next_sequence(sequence: &[i64]) -> Vec<i64> {
-    sequence.windows(2).map(|w| w[1] - w[0]).collect_vec()
-}
+fn part2(sequences: &[Vec<i64>]) -> Result<i64> {
+    let mut answer = 0;
+    for sequence in sequences {
+        let mut first_nums = Vec::new();
+        let mut sequence = sequence.clone();
+        while !sequence.iter().all(|x| *x == 0) {
+            let next_sequence = get_next_sequence(&sequence);
+            first_nums.push(*sequence.first().unwrap());
+            sequence = next_sequence;
+        }
 
-fn tests() -> anyhow::Result<()> {
-    let input = "0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45
-";
+        let mut next_num = 0;
+        for x in first_nums.iter().rev() {
+            next_num = *x - next_num;
+        }
 
-    let solution = solve(input)?;
+        answer += next_num;
+    }
 
-    assert_eq!(solution, 114);
+    Ok(answer)
+}
 
-    Ok(())
+fn get_next_sequence(sequence: &[i64]) -> Vec<i64> {
+    sequence.windows(2).map(|w| w[1] - w[0]).collect_vec()
 }